@@ -21,6 +21,12 @@ use ::core::{
     mem::{ManuallyDrop, ManuallyDrop as MD},
 };
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "std")]
+extern crate std;
+
 #[cfg(doc)]
 #[cfg_attr(feature = "better-docs", doc(cfg(doc)))]
 #[doc = include_str!("appendix.md")]
@@ -32,8 +38,245 @@ mod prelude {
     #[doc(no_inline)]
     pub use crate::{
         DropManually,
+        OnPanic,
         SafeManuallyDrop,
     };
+
+    #[cfg(feature = "derive")]
+    #[doc(no_inline)]
+    pub use crate::drop_manually;
+
+    #[cfg(feature = "std")]
+    #[doc(no_inline)]
+    pub use crate::drop_reason::{
+        DropManuallyWithReason,
+        DropReason,
+        WithReason,
+    };
+
+    #[doc(no_inline)]
+    pub use crate::context::{
+        DropManuallyWithContext,
+        SafeManuallyDropWithContext,
+    };
+
+    #[doc(no_inline)]
+    pub use crate::dyn_hook::DynSafeManuallyDrop;
+
+    #[doc(no_inline)]
+    pub use crate::close::{
+        Closeable,
+        CloseManually,
+    };
+
+    #[doc(no_inline)]
+    pub use crate::try_drop::{
+        Fallible,
+        TryDropManually,
+    };
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(feature = "better-docs", doc(cfg(feature = "std")))]
+pub mod drop_reason;
+
+pub mod close;
+
+pub mod context;
+
+pub mod defer;
+
+pub mod dyn_hook;
+
+#[cfg(feature = "std")]
+#[cfg_attr(feature = "better-docs", doc(cfg(feature = "std")))]
+pub mod journal;
+
+pub mod linear;
+
+pub mod must_consume;
+
+pub mod on_panic;
+#[doc(inline)]
+pub use on_panic::OnPanic;
+
+pub mod poison;
+
+pub mod policy;
+
+pub mod saga;
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "better-docs", doc(cfg(feature = "alloc")))]
+pub mod scope;
+#[cfg(feature = "alloc")]
+#[doc(inline)]
+pub use scope::scope;
+
+pub mod scope_guard;
+
+pub mod transaction;
+
+pub mod try_drop;
+
+pub mod two_phase;
+
+/// Attribute macro to get rid of the `impl<…> DropManually<FieldTy> for StructName<…> where … {`
+/// boilerplate.
+///
+/// Annotate the `struct` with `#[drop_manually]`, and each field needing owned access on drop with
+/// a <code>#[drop_manually(path::to::hook)]</code>, where `hook` is some `fn(FieldTy)`.
+///
+/// The macro then:
+///
+///   - wraps the type of such a field in a [`SafeManuallyDrop<FieldTy, Self, Tag>`], with a
+///     hidden, per-field, `Tag` type (so that several fields may share a `FieldTy`);
+///   - emits the companion
+///     <code>impl\<…\> [DropManually]\<FieldTy, Tag\> for StructName\<…\></code>, with the right
+///     generics and `where` clauses, which calls `hook(field)`.
+///
+/// ```rust
+/// use ::safe_manually_drop::prelude::*;
+///
+/// #[drop_manually]
+/// pub
+/// struct ScopeGuard<State, F : FnOnce(State)> {
+///     #[drop_manually(run_on_drop)]
+///     fields: ScopeGuardFields<State, F>,
+/// }
+///
+/// pub
+/// struct ScopeGuardFields<State, F : FnOnce(State)> {
+///     pub state: State,
+///     pub on_drop: F,
+/// }
+///
+/// fn run_on_drop<State, F : FnOnce(State)>(
+///     ScopeGuardFields { state, on_drop }: ScopeGuardFields<State, F>,
+/// )
+/// {
+///     on_drop(state);
+/// }
+///
+/// impl<State, F : FnOnce(State)> ScopeGuard<State, F> {
+///     pub
+///     fn defuse(self) -> ScopeGuardFields<State, F> {
+///         self.fields.into_inner_defusing_impl_Drop()
+///     }
+/// }
+///
+/// let counter = ::core::cell::Cell::new(0);
+/// drop(ScopeGuard {
+///     fields: ScopeGuardFields {
+///         state: &counter,
+///         on_drop: |counter: &::core::cell::Cell<_>| counter.set(counter.get() + 1),
+///     }.into(),
+/// });
+/// assert_eq!(counter.get(), 1);
+/// ```
+///
+/// ## Sibling-aware hooks
+///
+/// When the drop logic needs to look at some other field (_e.g._, some logger handle, or some
+/// config), the hook can be given `&`-access to sibling fields, with a
+/// <code>#[drop_manually(hook(\_, \&sibling, …))]</code> "call" syntax, wherein `_` stands for the
+/// owned field:
+///
+/// ```rust
+/// use ::safe_manually_drop::prelude::*;
+///
+/// # struct Logger;
+/// # impl Logger { fn log(&self, _: &str) {} }
+/// # struct RawConnection;
+/// # impl RawConnection { fn close(self) {} }
+/// #
+/// #[drop_manually]
+/// struct Connection<'logger> {
+///     #[drop_manually(Self::close_and_log(_, &logger))]
+///     raw: RawConnection,
+///     logger: &'logger Logger,
+/// }
+///
+/// impl Connection<'_> {
+///     fn close_and_log(raw: RawConnection, logger: &Logger) {
+///         logger.log("closing connection");
+///         raw.close();
+///     }
+/// }
+///
+/// let logger = &Logger;
+/// drop(Connection {
+///     raw: RawConnection.into(),
+///     logger,
+/// });
+/// ```
+///
+/// Such hooks get called, in field declaration order, from an `impl Drop` for the containing
+/// `struct`, _i.e._, before any of its fields gets dropped; should one panic, the later ones still
+/// run, as with the drop glue of plain fields. This has some implications:
+///
+///   - the annotated field is wrapped in a [`SiblingAwareField`] rather than a
+///     [`SafeManuallyDrop`];
+///
+///   - the containing `struct` cannot be destructured, nor have fields moved out of it (since
+///     it now has an `impl Drop`); so, if you need to defuse the drop logic of some field, stick
+///     to hooks not requiring sibling access;
+///
+///   - a hook cannot refer to a field which has, itself, a sibling-aware hook.
+#[cfg(feature = "derive")]
+#[cfg_attr(feature = "better-docs", doc(cfg(feature = "derive")))]
+pub use ::safe_manually_drop_proc_macros::drop_manually;
+
+/// The wrapper of a field with a [sibling-aware][drop_manually#sibling-aware-hooks]
+/// `#[drop_manually(…)]` hook.
+///
+/// Constructed with `.into()`, and accessed through [`Deref`]/[`DerefMut`]; its value is only
+/// ever moved out of it by the `impl Drop` of the containing `struct`.
+///
+/// [`Deref`]: ::core::ops::Deref
+/// [`DerefMut`]: ::core::ops::DerefMut
+#[cfg(feature = "derive")]
+#[cfg_attr(feature = "better-docs", doc(cfg(feature = "derive")))]
+pub
+struct SiblingAwareField<T>(
+    /// Only ever `None` once taken, _i.e._, within the `impl Drop` of the containing `struct`.
+    Option<T>,
+);
+
+#[cfg(feature = "derive")]
+impl<T> SiblingAwareField<T> {
+    #[doc(hidden)] /** Not part of the public API */
+    #[inline]
+    pub
+    fn __take(this: &mut Self) -> T {
+        this.0.take().expect("`SiblingAwareField` already taken")
+    }
+}
+
+#[cfg(feature = "derive")]
+impl<T> From<T> for SiblingAwareField<T> {
+    #[inline]
+    fn from(value: T) -> Self {
+        Self(Some(value))
+    }
+}
+
+#[cfg(feature = "derive")]
+impl<T> ::core::ops::Deref for SiblingAwareField<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        self.0.as_ref().expect("`SiblingAwareField` already taken")
+    }
+}
+
+#[cfg(feature = "derive")]
+impl<T> ::core::ops::DerefMut for SiblingAwareField<T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        self.0.as_mut().expect("`SiblingAwareField` already taken")
+    }
 }
 
 /// The main/whole point of this whole crate and design: to expose _owned_ access to a `FieldTy`
@@ -61,6 +304,11 @@ mod prelude {
 ///         [`From::from()`] and ["`.into()`"][`SafeManuallyDrop::into_inner_defusing_impl_Drop()`]
 ///         conversions.)
 ///
+///       - (It also forwards `Debug`, `Clone`, `Default`, `{Partial,}{Eq,Ord}`, `Hash`,
+///         `As{Ref,Mut}` and `Borrow{,Mut}` to its `FieldTy`, so that the containing type can
+///         keep `#[derive()]`-ing these; and, with the `serde` Cargo feature enabled,
+///         `Serialize` and `Deserialize` too.)
+///
 ///  1. then, provide the companion, mandatory,
 ///     <code>impl [DropManually\<FieldTy\>][`DropManually`] for ContainingType {</code>
 ///
@@ -135,11 +383,21 @@ In order for a struct/enum to contain a `SafeManuallyDrop<FieldTy, …>` field:
     ```rust
     impl<…> DropManually<FieldTy> for StructName<…> {{
     ```
+
+ 3. if a third, `Tag`, type parameter was provided, as in \
+    `SafeManuallyDrop<FieldTy, Self, Tag>`, then the impl ought to mention it as well.
+
+    Here, it would have to be: `impl<…> DropManually<{FieldTy}, {Tag}> for {Self} {{`.
 \
     ",
 )]
 pub
-trait DropManually<FieldTy> {
+trait DropManually<FieldTy, Tag = ()> {
+    /// What to do should [`Self::drop_manually()`] panic.
+    ///
+    /// Defaults to [`OnPanic::Propagate`]. See the [`on_panic`] module for more info.
+    const ON_PANIC: OnPanic = OnPanic::Propagate;
+
     fn drop_manually(_: FieldTy);
 }
 
@@ -398,6 +656,46 @@ trait DropManually<FieldTy> {
 /// }
 /// ```
 ///
+/// ### Tagging fields: the third type parameter
+///
+/// When the drop logic does need to stem from the containing type itself (_e.g._, when it is
+/// generic, or private), the field can instead be disambiguated through an (optional) third
+/// `Tag` type parameter, which defaults to `()`:
+/// <code>impl [DropManually]\<FieldTy, Tag\> for ContainingType {</code>.
+///
+/// ```rust
+/// use ::safe_manually_drop::prelude::*;
+///
+/// # mod some_lib {
+/// #     pub struct Transaction(());
+/// #     impl Transaction { pub fn commit(self) {} pub fn roll_back(self) {} }
+/// # }
+/// use some_lib::Transaction;
+///
+/// struct Transactions<Extra> {
+///     to_commit: SafeManuallyDrop<Transaction, Self, Commit>,
+///     to_roll_back: SafeManuallyDrop<Transaction, Self, RollBack>,
+///     extra: Extra,
+/// }
+///
+/// enum Commit {}
+/// impl<Extra> DropManually<Transaction, Commit> for Transactions<Extra> {
+///     fn drop_manually(txn: Transaction) {
+///         txn.commit();
+///     }
+/// }
+///
+/// enum RollBack {}
+/// impl<Extra> DropManually<Transaction, RollBack> for Transactions<Extra> {
+///     fn drop_manually(txn: Transaction) {
+///         txn.roll_back();
+///     }
+/// }
+/// ```
+///
+/// The other flavors of hooks ([`CloseManually`][close::CloseManually], _etc._) take such an
+/// optional `Tag` parameter as well.
+///
 /// ### `repr()` guarantee.
 ///
 /// This type is guaranteed to be a mere `#[repr(transparent)]` wrapper around its `FieldTy`.
@@ -425,11 +723,11 @@ trait DropManually<FieldTy> {
 ///     [`ManuallyDrop::take()`]-like APIs of any sort, and whatnot.
 #[repr(transparent)]
 pub
-struct SafeManuallyDrop<FieldTy, ContainingType = diagnostics::MissingSecondTypeParam>
+struct SafeManuallyDrop<FieldTy, ContainingType = diagnostics::MissingSecondTypeParam, Tag = ()>
 where
-    ContainingType : DropManually<FieldTy>,
+    ContainingType : DropManually<FieldTy, Tag>,
 {
-    _phantom: PD<fn() -> ContainingType>,
+    _phantom: PD<fn() -> (ContainingType, Tag)>,
     field: ManuallyDrop<FieldTy>,
 }
 
@@ -439,21 +737,21 @@ where
 /// blanket `impl`:
 ///
 /// <code>impl\<FieldTy\> Drop for SafeManuallyDrop\<FieldTy, …\> where … : DropManually\<FieldTy\> { </code>
-impl<FieldTy, ContainingType : DropManually<FieldTy>>
+impl<FieldTy, ContainingType : DropManually<FieldTy, Tag>, Tag>
     Drop
 for
-    SafeManuallyDrop<FieldTy, ContainingType>
+    SafeManuallyDrop<FieldTy, ContainingType, Tag>
 {
     #[inline]
     fn drop(&mut self) {
         let owned: FieldTy = unsafe {
             MD::take(&mut self.field)
         };
-        ContainingType::drop_manually(owned)
+        on_panic::drop_manually::<FieldTy, ContainingType, Tag>(owned)
     }
 }
 
-impl<FieldTy, ContainingType : DropManually<FieldTy>> SafeManuallyDrop<FieldTy, ContainingType> {
+impl<FieldTy, ContainingType : DropManually<FieldTy, Tag>, Tag> SafeManuallyDrop<FieldTy, ContainingType, Tag> {
     /// Main, `const`-friendly, way to construct a [`SafeManuallyDrop<FieldTy, _>`] instance.
     ///
     /// Alternatively, there is a <code>[From]\<FieldTy> impl</code> as well.
@@ -468,10 +766,10 @@ impl<FieldTy, ContainingType : DropManually<FieldTy>> SafeManuallyDrop<FieldTy,
     const
     fn new(value: FieldTy) -> Self {
         #[allow(non_local_definitions)]
-        impl<FieldTy, ContainingType : DropManually<FieldTy>>
+        impl<FieldTy, ContainingType : DropManually<FieldTy, Tag>, Tag>
             From<FieldTy>
         for
-            SafeManuallyDrop<FieldTy, ContainingType>
+            SafeManuallyDrop<FieldTy, ContainingType, Tag>
         {
             fn from(field: FieldTy) -> Self {
                 Self::new(field)
@@ -503,7 +801,7 @@ impl<FieldTy, ContainingType : DropManually<FieldTy>> SafeManuallyDrop<FieldTy,
             // Safety: `repr(transparent)`, and no extra validity nor safety invariants at play.
             MD::into_inner(
                 ConstUncheckedTransmuter::<
-                    SafeManuallyDrop<FieldTy, ContainingType>,
+                    SafeManuallyDrop<FieldTy, ContainingType, Tag>,
                     FieldTy,
                 >
                 {
@@ -513,12 +811,89 @@ impl<FieldTy, ContainingType : DropManually<FieldTy>> SafeManuallyDrop<FieldTy,
             )
         }
     }
+
+    /// Transforms the guarded value into a `U`, guarded by the `C2` drop logic, with no point at
+    /// which the resource is left unguarded (_e.g._, from a `Connection` to an
+    /// `AuthenticatedConnection`).
+    ///
+    /// This is an associated function, so as not to shadow the methods of the `FieldTy` (such as
+    /// [`Option::map()`]): call it as `SafeManuallyDrop::map(guard, f)`.
+    ///
+    /// # Panics
+    ///
+    /// Should `f` panic, the process is **aborted**: the value it owns can then no longer reach
+    /// either drop hook.
+    pub
+    fn map<U, C2 : DropManually<U, Tag>>(
+        this: Self,
+        f: impl FnOnce(FieldTy) -> U,
+    ) -> SafeManuallyDrop<U, C2, Tag>
+    {
+        let value = this.into_inner_defusing_impl_Drop();
+        let mapped = on_panic::abort_on_unwind::<FieldTy, ContainingType, _>("map", || f(value));
+        SafeManuallyDrop::new(mapped)
+    }
+
+    /// Swaps the guarded value for a `new` one, handing back the old one, _defused_ (as with
+    /// [`Self::into_inner_defusing_impl_Drop()`]).
+    ///
+    /// This is an associated function, so as not to shadow the methods of the `FieldTy` (such as
+    /// [`Cell::replace()`][::core::cell::Cell::replace]): call it as
+    /// `SafeManuallyDrop::replace(&mut guard, new)`.
+    #[inline]
+    pub
+    fn replace(this: &mut Self, new: FieldTy) -> FieldTy {
+        ::core::mem::replace(&mut this.field, new)
+    }
+
+    /// In-place counterpart of [`Self::map()`]: takes the value out, and re-arms the drop logic
+    /// with the output of `f`.
+    ///
+    /// Handy when only a `&mut` to the [`SafeManuallyDrop`] is available, such as for a field
+    /// within a `&mut self` method.
+    ///
+    /// Like [`Self::map()`], this is an associated function: call it as
+    /// `SafeManuallyDrop::take_and_rearm(&mut guard, f)`.
+    ///
+    /// # Panics
+    ///
+    /// Should `f` panic, the process is **aborted**: the value it owns can then no longer reach
+    /// the drop hook.
+    pub
+    fn take_and_rearm(this: &mut Self, f: impl FnOnce(FieldTy) -> FieldTy) {
+        // Safety: the `field` is vacant only while `f` runs, and, should it panic, the process
+        // aborts before anything gets to observe it.
+        let value = unsafe { MD::take(&mut this.field) };
+        let new = on_panic::abort_on_unwind::<FieldTy, ContainingType, _>(
+            "take_and_rearm",
+            || f(value),
+        );
+        this.field = MD::new(new);
+    }
+
+    /// Runs the drop logic right away, returning its output, rather than leaving it to the
+    /// implicit drop glue (which discards it).
+    ///
+    /// This requires the `ContainingType` to implement [`CloseManually`][close::CloseManually],
+    /// such as the [`Closeable`][close::Closeable] or [`Fallible`][try_drop::Fallible] adapters:
+    /// see the [`close`] module for more info.
+    ///
+    /// Unlike when implicitly dropped, no [`OnPanic`] policy is applied: the caller is in
+    /// charge.
+    #[inline]
+    pub
+    fn close(self) -> ContainingType::Output
+    where
+        ContainingType : close::CloseManually<FieldTy, Tag>,
+    {
+        ContainingType::close_manually(self.into_inner_defusing_impl_Drop())
+    }
 }
 
-impl<FieldTy, ContainingType : DropManually<FieldTy>>
+impl<FieldTy, ContainingType : DropManually<FieldTy, Tag>, Tag>
     ::core::ops::Deref
 for
-    SafeManuallyDrop<FieldTy, ContainingType>
+    SafeManuallyDrop<FieldTy, ContainingType, Tag>
 {
     type Target = FieldTy;
 
@@ -528,10 +903,10 @@ for
     }
 }
 
-impl<FieldTy, ContainingType : DropManually<FieldTy>>
+impl<FieldTy, ContainingType : DropManually<FieldTy, Tag>, Tag>
     ::core::ops::DerefMut
 for
-    SafeManuallyDrop<FieldTy, ContainingType>
+    SafeManuallyDrop<FieldTy, ContainingType, Tag>
 {
     #[inline]
     fn deref_mut(&mut self) -> &mut FieldTy {
@@ -539,6 +914,11 @@ for
     }
 }
 
+mod forwarded_impls;
+
+#[cfg(feature = "serde")]
+mod serde_impls;
+
 /// Some helper for a nicer diagnostic suggestion/nudge in case of a forgotten second type
 /// parameter.
 mod diagnostics {
@@ -546,7 +926,7 @@ mod diagnostics {
 
     pub enum MissingSecondTypeParam {}
 
-    impl<FieldTy> DropManually<FieldTy> for MissingSecondTypeParam
+    impl<FieldTy, Tag> DropManually<FieldTy, Tag> for MissingSecondTypeParam
     where
         for<'never_true> MissingSecondTypeParam : ExplicitlyProvided,
     {
@@ -561,9 +941,24 @@ mod diagnostics {
             Please use the containing `struct/enum` for it, such as: `Self`.\
         ",
         label = "help: use `SafeManuallyDrop<FieldTy, Self>` instead.",
+        note = "\
+            for a stock drop hook, without defining a type for it, a ready-made \
+            `::safe_manually_drop::policy` type can be used instead of `Self`, such as: \
+            `policy::Leak`, `policy::Abort`, `policy::PanicIfDropped`, or `policy::DropNormally`.\
+        ",
     )]
     pub trait ExplicitlyProvided {}
 }
 
+// macro internals
+#[doc(hidden)] /** Not part of the public API */ pub
+mod ඞ {
+    pub use ::core; // or `std`
+}
+
 #[doc = include_str!("compile_fail_tests.md")]
 mod _compile_fail_tests {}
+
+#[cfg(feature = "derive")]
+#[doc = include_str!("drop_manually_compile_fail_tests.md")]
+mod _drop_manually_compile_fail_tests {}
@@ -0,0 +1,144 @@
+//! Explicitly running the drop logic of a [`SafeManuallyDrop`], and getting its output back:
+//! [`SafeManuallyDrop::close()`].
+//!
+//! The implicit drop glue has nowhere to return anything to, so it can only ever be a best-effort
+//! fallback; whereas APIs often also want to offer an explicit `fn close(self) -> io::Result<()>`.
+//! Rather than duplicating the hook logic, write it once, as a [`CloseManually`] impl:
+//!
+//! ```rust
+//! use ::safe_manually_drop::prelude::*;
+//!
+//! # mod some_lib {
+//! #     pub struct RawFile(());
+//! #     impl RawFile { pub fn close(self) -> ::std::io::Result<()> { Ok(()) } }
+//! # }
+//! use some_lib::RawFile;
+//!
+//! pub
+//! struct File {
+//!     raw: SafeManuallyDrop<RawFile, Closeable<Self>>,
+//! }
+//!
+//! impl CloseManually<RawFile> for File {
+//!     type Output = ::std::io::Result<()>;
+//!
+//!     fn close_manually(raw: RawFile) -> ::std::io::Result<()> {
+//!         raw.close()
+//!     }
+//! }
+//!
+//! impl File {
+//!     /// Dropping a `File` also closes it, but silently ignores any error.
+//!     pub
+//!     fn close(self) -> ::std::io::Result<()> {
+//!         self.raw.close()
+//!     }
+//! }
+//! ```
+//!
+//! [`SafeManuallyDrop::close()`] is also available for the other hook flavors:
+//!
+//!   - with a [`Fallible`][crate::try_drop::Fallible] `ContainingType`, it returns the
+//!     `Result<(), Error>` of the [`TryDropManually`][crate::try_drop::TryDropManually] hook
+//!     (rather than reporting the error to the sinks);
+//!
+//!   - with a `WithReason` one, it calls the hook with `DropReason::Normal`;
+//!
+//!   - with a plain `ContainingType`, an <code>impl [CloseManually]\<FieldTy\></code> with
+//!     `type Output = ();` forwarding to the [`DropManually`] one does the trick (since
+//!     associated types cannot have defaults in stable Rust).
+
+use super::*;
+
+/// A drop hook which produces some [`Output`][Self::Output], returned by
+/// [`SafeManuallyDrop::close()`], and discarded when implicitly dropped.
+///
+/// In order to use it, rather than `SafeManuallyDrop<FieldTy, Self>`, the field ought to be a
+/// <code>[SafeManuallyDrop]\<FieldTy, [Closeable]\<Self\>\></code>.
+#[diagnostic::on_unimplemented(
+    note = "\
+In order for a `SafeManuallyDrop<FieldTy, Closeable<ContainingType>>` to be used, you have to \
+provide an `impl<…> CloseManually<FieldTy> for ContainingType<…> {{`.\
+    ",
+)]
+pub
+trait CloseManually<FieldTy, Tag = ()> {
+    type Output;
+
+    /// Forwarded to [`DropManually::ON_PANIC`] (only relevant to the implicit drop).
+    const ON_PANIC: OnPanic = OnPanic::Propagate;
+
+    fn close_manually(_: FieldTy) -> Self::Output;
+}
+
+/// The `ContainingType` adapter for a [`CloseManually`] impl to be used as the drop logic of a
+/// [`SafeManuallyDrop`].
+///
+/// ```rust
+/// # use ::safe_manually_drop::prelude::*;
+/// # struct Example {
+/// field: SafeManuallyDrop<String, Closeable<Self>>,
+/// # }
+/// # impl CloseManually<String> for Example {
+/// #     type Output = ();
+/// #     fn close_manually(_: String) {}
+/// # }
+/// ```
+pub
+struct Closeable<ContainingType>(
+    ::core::convert::Infallible,
+    PD<fn() -> ContainingType>,
+);
+
+impl<FieldTy, ContainingType : CloseManually<FieldTy, Tag>, Tag>
+    DropManually<FieldTy, Tag>
+for
+    Closeable<ContainingType>
+{
+    const ON_PANIC: OnPanic = ContainingType::ON_PANIC;
+
+    #[inline]
+    fn drop_manually(field: FieldTy) {
+        let _ = ContainingType::close_manually(field);
+    }
+}
+
+impl<FieldTy, ContainingType : CloseManually<FieldTy, Tag>, Tag>
+    CloseManually<FieldTy, Tag>
+for
+    Closeable<ContainingType>
+{
+    type Output = ContainingType::Output;
+
+    #[inline]
+    fn close_manually(field: FieldTy) -> Self::Output {
+        ContainingType::close_manually(field)
+    }
+}
+
+impl<FieldTy, ContainingType : try_drop::TryDropManually<FieldTy, Tag>, Tag>
+    CloseManually<FieldTy, Tag>
+for
+    try_drop::Fallible<ContainingType>
+{
+    type Output = Result<(), ContainingType::Error>;
+
+    #[inline]
+    fn close_manually(field: FieldTy) -> Self::Output {
+        ContainingType::try_drop_manually(field)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<FieldTy, ContainingType : drop_reason::DropManuallyWithReason<FieldTy, Tag>, Tag>
+    CloseManually<FieldTy, Tag>
+for
+    drop_reason::WithReason<ContainingType>
+{
+    type Output = ();
+
+    #[inline]
+    fn close_manually(field: FieldTy) {
+        ContainingType::drop_manually_with_reason(field, drop_reason::DropReason::Normal)
+    }
+}
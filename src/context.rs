@@ -0,0 +1,167 @@
+//! Drop hooks with some per-instance [`Context`][DropManuallyWithContext::Context], stored next to
+//! the field: [`SafeManuallyDropWithContext`].
+//!
+//! ```rust
+//! use ::safe_manually_drop::prelude::*;
+//! use ::std::sync::mpsc;
+//!
+//! /// Hands the buffer back to its pool on drop.
+//! struct PooledBuf {
+//!     buf: SafeManuallyDropWithContext<Vec<u8>, Self>,
+//! }
+//!
+//! impl DropManuallyWithContext<Vec<u8>> for PooledBuf {
+//!     type Context = mpsc::Sender<Vec<u8>>;
+//!
+//!     fn drop_manually_with_context(mut buf: Vec<u8>, pool: mpsc::Sender<Vec<u8>>) {
+//!         buf.clear();
+//!         let _ = pool.send(buf);
+//!     }
+//! }
+//!
+//! let (pool, recycled) = mpsc::channel();
+//! let it = PooledBuf {
+//!     buf: SafeManuallyDropWithContext::new(vec![42], pool),
+//! };
+//! assert_eq!(*it.buf, [42]);
+//! drop(it);
+//! assert!(recycled.recv().unwrap().is_empty());
+//! ```
+
+use super::*;
+
+/// Same as [`DropManually`], but for the hook also receiving, by value, some
+/// [`Context`][Self::Context] provided when the guard was created.
+///
+/// In order to use it, rather than `SafeManuallyDrop<FieldTy, Self>`, the field ought to be a
+/// <code>[SafeManuallyDropWithContext]\<FieldTy, Self\></code>.
+#[diagnostic::on_unimplemented(
+    note = "\
+In order for a `SafeManuallyDropWithContext<FieldTy, ContainingType>` to be used, you have to \
+provide an `impl<…> DropManuallyWithContext<FieldTy> for ContainingType<…> {{`.\
+    ",
+)]
+pub
+trait DropManuallyWithContext<FieldTy, Tag = ()> {
+    /// The per-instance data, stored next to the `FieldTy`.
+    type Context;
+
+    /// Forwarded to [`DropManually::ON_PANIC`].
+    const ON_PANIC: OnPanic = OnPanic::Propagate;
+
+    fn drop_manually_with_context(_: FieldTy, context: Self::Context);
+}
+
+/// A [`SafeManuallyDrop<FieldTy, …>`] which also stores the
+/// [`Context`][DropManuallyWithContext::Context] for its drop hook.
+///
+/// It [`Deref`][::core::ops::Deref]s to the `FieldTy`; the context can be accessed through
+/// [`Self::context()`].
+pub
+struct SafeManuallyDropWithContext<FieldTy, ContainingType, Tag = ()>
+where
+    ContainingType : DropManuallyWithContext<FieldTy, Tag>,
+{
+    inner: SafeManuallyDrop<
+        (FieldTy, ContainingType::Context),
+        WithContext<ContainingType, FieldTy>,
+        Tag,
+    >,
+}
+
+/// The internal `ContainingType` adapter, unpacking the stored `(FieldTy, Context)` pair.
+pub(crate)
+struct WithContext<ContainingType, FieldTy>(
+    ::core::convert::Infallible,
+    PD<fn() -> (ContainingType, FieldTy)>,
+);
+
+impl<FieldTy, ContainingType : DropManuallyWithContext<FieldTy, Tag>, Tag>
+    DropManually<(FieldTy, ContainingType::Context), Tag>
+for
+    WithContext<ContainingType, FieldTy>
+{
+    const ON_PANIC: OnPanic = ContainingType::ON_PANIC;
+
+    #[inline]
+    fn drop_manually((field, context): (FieldTy, ContainingType::Context)) {
+        ContainingType::drop_manually_with_context(field, context)
+    }
+}
+
+impl<FieldTy, ContainingType : DropManuallyWithContext<FieldTy, Tag>, Tag>
+    SafeManuallyDropWithContext<FieldTy, ContainingType, Tag>
+{
+    /// Arms the drop hook of `value`, which shall be called with `context`.
+    #[inline]
+    pub
+    const
+    fn new(value: FieldTy, context: ContainingType::Context) -> Self {
+        Self {
+            inner: SafeManuallyDrop::new((value, context)),
+        }
+    }
+
+    /// The context to be handed to the drop hook.
+    #[inline]
+    pub
+    fn context(&self) -> &ContainingType::Context {
+        &self.inner.1
+    }
+
+    /// The context to be handed to the drop hook.
+    #[inline]
+    pub
+    fn context_mut(&mut self) -> &mut ContainingType::Context {
+        &mut self.inner.1
+    }
+
+    /// Same as [`SafeManuallyDrop::into_inner_defusing_impl_Drop()`], but also yielding back the
+    /// context.
+    #[inline]
+    #[allow(nonstandard_style)]
+    pub
+    fn into_inner_defusing_impl_Drop(self) -> (FieldTy, ContainingType::Context) {
+        self.inner.into_inner_defusing_impl_Drop()
+    }
+}
+
+impl<FieldTy, ContainingType : DropManuallyWithContext<FieldTy, Tag>, Tag>
+    ::core::ops::Deref
+for
+    SafeManuallyDropWithContext<FieldTy, ContainingType, Tag>
+{
+    type Target = FieldTy;
+
+    #[inline]
+    fn deref(&self) -> &FieldTy {
+        &self.inner.0
+    }
+}
+
+impl<FieldTy, ContainingType : DropManuallyWithContext<FieldTy, Tag>, Tag>
+    ::core::ops::DerefMut
+for
+    SafeManuallyDropWithContext<FieldTy, ContainingType, Tag>
+{
+    #[inline]
+    fn deref_mut(&mut self) -> &mut FieldTy {
+        &mut self.inner.0
+    }
+}
+
+impl<FieldTy, ContainingType : DropManuallyWithContext<FieldTy, Tag>, Tag>
+    ::core::fmt::Debug
+for
+    SafeManuallyDropWithContext<FieldTy, ContainingType, Tag>
+where
+    FieldTy : ::core::fmt::Debug,
+    ContainingType::Context : ::core::fmt::Debug,
+{
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        f   .debug_struct("SafeManuallyDropWithContext")
+            .field("field", &self.inner.0)
+            .field("context", &self.inner.1)
+            .finish()
+    }
+}
@@ -0,0 +1,219 @@
+//! Running some code when a scope is exited: [`Defer`], and the [`defer!`][crate::defer!] macro.
+//!
+//! ```rust
+//! use ::safe_manually_drop::defer;
+//!
+//! let log = ::core::cell::RefCell::new(vec![]);
+//! {
+//!     defer! { log.borrow_mut().push("first defer!, last to run") }
+//!     defer! { log.borrow_mut().push("last defer!, first to run") }
+//!     log.borrow_mut().push("scope body");
+//! }
+//! assert_eq!(log.into_inner(), [
+//!     "scope body",
+//!     "last defer!, first to run",
+//!     "first defer!, last to run",
+//! ]);
+//! ```
+//!
+//! Deferred closures run even when the scope is exited through unwinding. Should one panic,
+//! the panic propagates as with any other drop glue (see [`OnPanic`]).
+//!
+#![cfg_attr(feature = "alloc", doc = "\
+    When the number of deferred actions is only known at runtime, see [`DeferStack`].\
+")]
+
+use super::*;
+#[cfg(feature = "alloc")]
+use ::alloc::{boxed::Box, rc::{Rc, Weak}, vec::Vec};
+
+/// Calls the `F` closure when dropped, unless [`Self::cancel()`]-ed.
+///
+/// ```rust
+/// use ::safe_manually_drop::defer::Defer;
+///
+/// let mut ran = false;
+/// {
+///     let _guard = Defer::new(|| ran = true);
+/// }
+/// assert!(ran);
+///
+/// let guard = Defer::new(|| unreachable!());
+/// let _f = guard.cancel();
+/// ```
+pub
+struct Defer<F : FnOnce()>(
+    SafeManuallyDrop<F, Self>,
+);
+
+impl<F : FnOnce()> DropManually<F> for Defer<F> {
+    #[inline]
+    fn drop_manually(f: F) {
+        f();
+    }
+}
+
+impl<F : FnOnce()> Defer<F> {
+    #[inline]
+    pub
+    const
+    fn new(f: F) -> Self {
+        Self(SafeManuallyDrop::new(f))
+    }
+
+    /// Defuses the guard, handing the closure back without calling it.
+    #[inline]
+    pub
+    fn cancel(self) -> F {
+        self.0.into_inner_defusing_impl_Drop()
+    }
+}
+
+/// Statement macro to run the given code when the current scope is exited.
+///
+/// `defer! { body }` is sugar for `let _guard = Defer::new(|| { body });`, with an unnameable
+/// `_guard`. Multiple `defer!`s in the same scope run in reverse order (LIFO).
+///
+/// See the [`defer`][mod@crate::defer] module for an example.
+#[macro_export]
+macro_rules! defer {( $($body:tt)* ) => (
+    let _deferred = $crate::defer::Defer::new(|| { $($body)* });
+)}
+
+/// A runtime-sized stack of deferred actions, run in reverse order (LIFO) when dropped.
+///
+/// Each entry owns its data, and can be [`.disarm()`][Self::disarm]ed (or
+/// [`.remove()`][Self::remove]d) through the [`DeferHandle`] returned when pushing it.
+///
+/// ```rust
+/// use ::safe_manually_drop::defer::DeferStack;
+///
+/// let log = ::core::cell::RefCell::new(vec![]);
+/// {
+///     let mut stack = DeferStack::new();
+///     let handles: Vec<_> =
+///         (0..4)
+///             .map(|i| stack.push(i, |i| log.borrow_mut().push(i)))
+///             .collect()
+///     ;
+///     let [_, one, ..] = <[_; 4]>::try_from(handles).ok().unwrap();
+///     stack.disarm(one);
+/// }
+/// assert_eq!(log.into_inner(), [3, 2, 0]);
+/// ```
+///
+/// Should an action panic, the remaining ones still run.
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "better-docs", doc(cfg(feature = "alloc")))]
+pub
+struct DeferStack<'a> {
+    entries: SafeManuallyDrop<Entries<'a>, Self>,
+    /// The identity of the stack, checked against that of the [`DeferHandle`]s (lazily
+    /// allocated, for [`DeferStack::new()`] to be `const`).
+    id: Option<Rc<()>>,
+}
+
+/// Disarmed entries are `None`, so that the indices of the others stay put.
+#[cfg(feature = "alloc")]
+type Entries<'a> = Vec<Option<Box<dyn 'a + FnOnce()>>>;
+
+/// Identifies an entry of the [`DeferStack`] which issued it.
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "better-docs", doc(cfg(feature = "alloc")))]
+#[derive(Debug)]
+pub
+struct DeferHandle {
+    index: usize,
+    /// Keeps the identity allocation alive, so that no other stack may end up reusing it.
+    stack: Weak<()>,
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> DropManually<Entries<'a>> for DeferStack<'a> {
+    fn drop_manually(mut actions: Entries<'a>) {
+        while let Some(action) = actions.pop() {
+            if let Some(action) = action {
+                // So that, even if `action` panics, the remaining ones still run.
+                let rest = SafeManuallyDrop::<_, Self>::new(::core::mem::take(&mut actions));
+                action();
+                actions = rest.into_inner_defusing_impl_Drop();
+            }
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> DeferStack<'a> {
+    #[inline]
+    pub
+    const
+    fn new() -> Self {
+        Self {
+            entries: SafeManuallyDrop::new(Vec::new()),
+            id: None,
+        }
+    }
+
+    /// Defers `action(data)`.
+    #[inline]
+    pub
+    fn push<T : 'a>(&mut self, data: T, action: impl 'a + FnOnce(T)) -> DeferHandle {
+        self.push_fn(move || action(data))
+    }
+
+    /// Defers a mere closure.
+    pub
+    fn push_fn(&mut self, action: impl 'a + FnOnce()) -> DeferHandle {
+        let stack = Rc::downgrade(self.id.get_or_insert_with(Rc::default));
+        let index = self.entries.len();
+        self.entries.push(Some(Box::new(action)));
+        DeferHandle { index, stack }
+    }
+
+    /// Removes the entry, handing its action back, without running it.
+    ///
+    /// # Panics
+    ///
+    /// If the handle was issued by another stack.
+    pub
+    fn remove(&mut self, handle: DeferHandle) -> Box<dyn 'a + FnOnce()> {
+        let issued_here = matches!(&self.id, Some(id) if handle.stack.ptr_eq(&Rc::downgrade(id)));
+        assert!(issued_here, "`DeferHandle` issued by another `DeferStack`");
+        let action =
+            self.entries[handle.index]
+                .take()
+                .expect("handles are consumed, so their entry is still armed")
+        ;
+        // Keep the stack from growing with disarmed entries, when used in a LIFO fashion.
+        while let Some(None) = self.entries.last() {
+            self.entries.pop();
+        }
+        action
+    }
+
+    /// Removes the entry, dropping its action without running it.
+    ///
+    /// # Panics
+    ///
+    /// If the handle was issued by another stack.
+    #[inline]
+    pub
+    fn disarm(&mut self, handle: DeferHandle) {
+        drop(self.remove(handle));
+    }
+
+    /// Defuses every entry: the actions are dropped without being run.
+    #[inline]
+    pub
+    fn release(self) {
+        drop(self.entries.into_inner_defusing_impl_Drop());
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Default for DeferStack<'_> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,106 @@
+//! [`DropReason`]-aware drop hooks: _normal_ scope exit _vs._ unwinding.
+//!
+//! ```rust
+//! use ::safe_manually_drop::prelude::*;
+//!
+//! # mod some_lib {
+//! #     pub struct Transaction(());
+//! #     impl Transaction { pub fn roll_back(self) {} }
+//! # }
+//! use some_lib::Transaction;
+//!
+//! pub
+//! struct WrappedTransaction {
+//!     txn: SafeManuallyDrop<Transaction, WithReason<Self>>,
+//! }
+//!
+//! impl DropManuallyWithReason<Transaction> for WrappedTransaction {
+//!     fn drop_manually_with_reason(txn: Transaction, reason: DropReason) {
+//!         if reason.is_unwinding() {
+//!             eprintln!("rolling back transaction due to a panic!");
+//!         }
+//!         txn.roll_back();
+//!     }
+//! }
+//! ```
+
+use super::*;
+
+/// Why some [`SafeManuallyDrop<FieldTy, …>`] is being dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub
+enum DropReason {
+    /// Normal scope exit (or explicit discarding, such as through [`drop()`]).
+    Normal,
+
+    /// A panic is unwinding through the value.
+    Unwinding,
+}
+
+impl DropReason {
+    /// Queries [`::std::thread::panicking()`] to figure out the current [`DropReason`].
+    #[inline]
+    pub
+    fn current() -> Self {
+        if ::std::thread::panicking() {
+            Self::Unwinding
+        } else {
+            Self::Normal
+        }
+    }
+
+    #[inline]
+    pub
+    fn is_unwinding(self) -> bool {
+        matches!(self, Self::Unwinding)
+    }
+}
+
+/// Same as [`DropManually`], but for the hook also being told _why_ the field is being dropped.
+///
+/// In order to use it, rather than `SafeManuallyDrop<FieldTy, Self>`, the field ought to be a
+/// <code>[SafeManuallyDrop]\<FieldTy, [WithReason]\<Self\>\></code>.
+#[diagnostic::on_unimplemented(
+    note = "\
+In order for a `SafeManuallyDrop<FieldTy, WithReason<ContainingType>>` to be used, you have to \
+provide an `impl<…> DropManuallyWithReason<FieldTy> for ContainingType<…> {{`.\
+    ",
+)]
+pub
+trait DropManuallyWithReason<FieldTy, Tag = ()> {
+    /// Forwarded to [`DropManually::ON_PANIC`].
+    const ON_PANIC: OnPanic = OnPanic::Propagate;
+
+    fn drop_manually_with_reason(_: FieldTy, reason: DropReason);
+}
+
+/// The `ContainingType` adapter for a [`DropManuallyWithReason`] impl to be used as the drop
+/// logic of a [`SafeManuallyDrop`].
+///
+/// ```rust
+/// # use ::safe_manually_drop::prelude::*;
+/// # struct Example {
+/// field: SafeManuallyDrop<String, WithReason<Self>>,
+/// # }
+/// # impl DropManuallyWithReason<String> for Example {
+/// #     fn drop_manually_with_reason(_: String, _: DropReason) {}
+/// # }
+/// ```
+pub
+struct WithReason<ContainingType>(
+    ::core::convert::Infallible,
+    PD<fn() -> ContainingType>,
+);
+
+impl<FieldTy, ContainingType : DropManuallyWithReason<FieldTy, Tag>, Tag>
+    DropManually<FieldTy, Tag>
+for
+    WithReason<ContainingType>
+{
+    const ON_PANIC: OnPanic = ContainingType::ON_PANIC;
+
+    #[inline]
+    fn drop_manually(field: FieldTy) {
+        ContainingType::drop_manually_with_reason(field, DropReason::current())
+    }
+}
@@ -0,0 +1,126 @@
+//! Drop hooks chosen at runtime, rather than by a [`DropManually`] impl: [`DynSafeManuallyDrop`].
+//!
+//! ```rust
+//! use ::safe_manually_drop::dyn_hook::DynSafeManuallyDrop;
+//!
+//! fn flush(buf: Vec<u8>) { /* … */ }
+//! fn discard(_: Vec<u8>) {}
+//!
+//! # let config_says_flush = true;
+//! let buf = DynSafeManuallyDrop::new(
+//!     vec![],
+//!     if config_says_flush { flush } else { discard },
+//! );
+//! ```
+//!
+//! The hook is any `FnOnce(FieldTy)`, defaulting to a thin `fn(FieldTy)` pointer.
+#![cfg_attr(feature = "alloc", doc = "\
+    Capturing closures can be type-erased into a [`BoxedHook`] with \
+    [`DynSafeManuallyDrop::new_boxed()`] (requires `alloc`).\
+")]
+
+use super::*;
+use context::{DropManuallyWithContext, SafeManuallyDropWithContext};
+
+/// A [`SafeManuallyDrop`] whose drop hook is provided at construction time: see
+/// [the module docs][self].
+///
+/// It [`Deref`][::core::ops::Deref]s to the `FieldTy`.
+pub
+struct DynSafeManuallyDrop<FieldTy, Hook : FnOnce(FieldTy) = fn(FieldTy)>(
+    SafeManuallyDropWithContext<FieldTy, Self>,
+);
+
+/// A type-erased, capturing, [`DynSafeManuallyDrop`] hook.
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "better-docs", doc(cfg(feature = "alloc")))]
+pub
+type BoxedHook<'a, FieldTy> = ::alloc::boxed::Box<dyn 'a + FnOnce(FieldTy)>;
+
+impl<FieldTy, Hook : FnOnce(FieldTy)>
+    DropManuallyWithContext<FieldTy>
+for
+    DynSafeManuallyDrop<FieldTy, Hook>
+{
+    type Context = Hook;
+
+    #[inline]
+    fn drop_manually_with_context(value: FieldTy, hook: Hook) {
+        hook(value);
+    }
+}
+
+impl<FieldTy, Hook : FnOnce(FieldTy)> DynSafeManuallyDrop<FieldTy, Hook> {
+    /// Arms `hook(value)`, to be called when dropped.
+    #[inline]
+    pub
+    const
+    fn new(value: FieldTy, hook: Hook) -> Self {
+        Self(SafeManuallyDropWithContext::new(value, hook))
+    }
+
+    #[inline]
+    pub
+    fn hook(&self) -> &Hook {
+        self.0.context()
+    }
+
+    /// Swaps the hook to be called when dropped, returning the previous one.
+    #[inline]
+    pub
+    fn replace_hook(&mut self, hook: Hook) -> Hook {
+        ::core::mem::replace(self.0.context_mut(), hook)
+    }
+
+    /// Same as [`SafeManuallyDrop::into_inner_defusing_impl_Drop()`]: the hook is dropped without
+    /// being called.
+    #[inline]
+    #[allow(nonstandard_style)]
+    pub
+    fn into_inner_defusing_impl_Drop(self) -> FieldTy {
+        self.0.into_inner_defusing_impl_Drop().0
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "better-docs", doc(cfg(feature = "alloc")))]
+impl<'a, FieldTy> DynSafeManuallyDrop<FieldTy, BoxedHook<'a, FieldTy>> {
+    /// Arms `hook(value)`, to be called when dropped, type-erasing the (capturing) `hook`.
+    #[inline]
+    pub
+    fn new_boxed(value: FieldTy, hook: impl 'a + FnOnce(FieldTy)) -> Self {
+        Self::new(value, ::alloc::boxed::Box::new(hook))
+    }
+}
+
+impl<FieldTy, Hook : FnOnce(FieldTy)> ::core::ops::Deref for DynSafeManuallyDrop<FieldTy, Hook> {
+    type Target = FieldTy;
+
+    #[inline]
+    fn deref(&self) -> &FieldTy {
+        &self.0
+    }
+}
+
+impl<FieldTy, Hook : FnOnce(FieldTy)>
+    ::core::ops::DerefMut
+for
+    DynSafeManuallyDrop<FieldTy, Hook>
+{
+    #[inline]
+    fn deref_mut(&mut self) -> &mut FieldTy {
+        &mut self.0
+    }
+}
+
+impl<FieldTy : ::core::fmt::Debug, Hook : FnOnce(FieldTy)>
+    ::core::fmt::Debug
+for
+    DynSafeManuallyDrop<FieldTy, Hook>
+{
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        f   .debug_struct("DynSafeManuallyDrop")
+            .field("field", &**self)
+            .finish_non_exhaustive()
+    }
+}
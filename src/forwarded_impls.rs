@@ -0,0 +1,156 @@
+//! Transparent, bound-forwarding, impls of the usual value traits, so that a containing type
+//! can keep `#[derive()]`-ing them.
+//!
+//! The only exception is [`Clone`] (and [`Default`]), which yield a _new_ guard, with its own,
+//! armed, drop glue.
+
+use super::*;
+
+impl<FieldTy, ContainingType : DropManually<FieldTy, Tag>, Tag>
+    ::core::fmt::Debug
+for
+    SafeManuallyDrop<FieldTy, ContainingType, Tag>
+where
+    FieldTy : ::core::fmt::Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        <FieldTy as ::core::fmt::Debug>::fmt(&self.field, f)
+    }
+}
+
+/// Each clone is a distinct instance, which shall thus be running
+/// <code>\<ContainingType as [DropManually]\<FieldTy\>\>::drop_manually()</code> on its own
+/// `FieldTy` value, once it gets dropped.
+impl<FieldTy, ContainingType : DropManually<FieldTy, Tag>, Tag>
+    Clone
+for
+    SafeManuallyDrop<FieldTy, ContainingType, Tag>
+where
+    FieldTy : Clone,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        Self::new(FieldTy::clone(&self.field))
+    }
+
+    // No `clone_from()` override: the default `*self = source.clone()` is what runs the hook
+    // of the overwritten value.
+}
+
+impl<FieldTy, ContainingType : DropManually<FieldTy, Tag>, Tag>
+    Default
+for
+    SafeManuallyDrop<FieldTy, ContainingType, Tag>
+where
+    FieldTy : Default,
+{
+    #[inline]
+    fn default() -> Self {
+        Self::new(FieldTy::default())
+    }
+}
+
+impl<FieldTy, ContainingType : DropManually<FieldTy, Tag>, Tag>
+    PartialEq
+for
+    SafeManuallyDrop<FieldTy, ContainingType, Tag>
+where
+    FieldTy : PartialEq,
+{
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        FieldTy::eq(&self.field, &other.field)
+    }
+}
+
+impl<FieldTy, ContainingType : DropManually<FieldTy, Tag>, Tag>
+    Eq
+for
+    SafeManuallyDrop<FieldTy, ContainingType, Tag>
+where
+    FieldTy : Eq,
+{}
+
+impl<FieldTy, ContainingType : DropManually<FieldTy, Tag>, Tag>
+    PartialOrd
+for
+    SafeManuallyDrop<FieldTy, ContainingType, Tag>
+where
+    FieldTy : PartialOrd,
+{
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<::core::cmp::Ordering> {
+        FieldTy::partial_cmp(&self.field, &other.field)
+    }
+}
+
+impl<FieldTy, ContainingType : DropManually<FieldTy, Tag>, Tag>
+    Ord
+for
+    SafeManuallyDrop<FieldTy, ContainingType, Tag>
+where
+    FieldTy : Ord,
+{
+    #[inline]
+    fn cmp(&self, other: &Self) -> ::core::cmp::Ordering {
+        FieldTy::cmp(&self.field, &other.field)
+    }
+}
+
+impl<FieldTy, ContainingType : DropManually<FieldTy, Tag>, Tag>
+    ::core::hash::Hash
+for
+    SafeManuallyDrop<FieldTy, ContainingType, Tag>
+where
+    FieldTy : ::core::hash::Hash,
+{
+    #[inline]
+    fn hash<H : ::core::hash::Hasher>(&self, state: &mut H) {
+        FieldTy::hash(&self.field, state)
+    }
+}
+
+impl<FieldTy, ContainingType : DropManually<FieldTy, Tag>, Tag>
+    AsRef<FieldTy>
+for
+    SafeManuallyDrop<FieldTy, ContainingType, Tag>
+{
+    #[inline]
+    fn as_ref(&self) -> &FieldTy {
+        &self.field
+    }
+}
+
+impl<FieldTy, ContainingType : DropManually<FieldTy, Tag>, Tag>
+    AsMut<FieldTy>
+for
+    SafeManuallyDrop<FieldTy, ContainingType, Tag>
+{
+    #[inline]
+    fn as_mut(&mut self) -> &mut FieldTy {
+        &mut self.field
+    }
+}
+
+impl<FieldTy, ContainingType : DropManually<FieldTy, Tag>, Tag>
+    ::core::borrow::Borrow<FieldTy>
+for
+    SafeManuallyDrop<FieldTy, ContainingType, Tag>
+{
+    #[inline]
+    fn borrow(&self) -> &FieldTy {
+        &self.field
+    }
+}
+
+impl<FieldTy, ContainingType : DropManually<FieldTy, Tag>, Tag>
+    ::core::borrow::BorrowMut<FieldTy>
+for
+    SafeManuallyDrop<FieldTy, ContainingType, Tag>
+{
+    #[inline]
+    fn borrow_mut(&mut self) -> &mut FieldTy {
+        &mut self.field
+    }
+}
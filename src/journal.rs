@@ -0,0 +1,257 @@
+//! A durable, on-disk, rollback journal, for compensations which must survive a crash (or a
+//! `kill -9`): [`Journal`].
+//!
+//! The drop hooks of a [`SafeManuallyDrop`] only run if the process keeps going. For things such
+//! as file-system staging, this may not be enough: the [`JournalGuard`]s of this module thus
+//! persist their pending compensation, as a [`Record`], in a journal directory, for as long as
+//! they are armed. Should the process die in the meantime, [`Journal::recover()`], at the next
+//! startup, replays the leftover compensations.
+//!
+//! ```rust
+//! use ::safe_manually_drop::journal::{Journal, Record};
+//! use ::std::{fs, io, path::PathBuf};
+//!
+//! /// Compensation: remove a staged file.
+//! struct Unstage(PathBuf);
+//!
+//! impl Record for Unstage {
+//!     fn encode(&self) -> Vec<u8> {
+//!         self.0.to_str().unwrap().into()
+//!     }
+//!
+//!     fn decode(bytes: &[u8]) -> io::Result<Self> {
+//!         String::from_utf8(bytes.into())
+//!             .map(|s| Self(s.into()))
+//!             .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+//!     }
+//!
+//!     fn compensate(self) -> io::Result<()> {
+//!         match fs::remove_file(self.0) {
+//!             | Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+//!             | res => res,
+//!         }
+//!     }
+//! }
+//!
+//! fn stage(journal: &Journal<Unstage>, path: PathBuf) -> io::Result<()> {
+//!     let guard = journal.guard(Unstage(path.clone()))?;
+//!     fs::write(&path, "staged contents")?; // on error, or crash, `path` gets removed.
+//!     // …
+//!     guard.commit()
+//! }
+//!
+//! # let dir = ::std::env::temp_dir().join(format!("journal-doctest-{}", ::std::process::id()));
+//! let journal = Journal::<Unstage>::open(dir.join("journal"))?;
+//! // At startup: undo whatever a previous, crashed, run left half-done.
+//! journal.recover()?;
+//! stage(&journal, dir.join("staged.txt"))?;
+//! # fs::remove_dir_all(dir)?;
+//! # Ok::<(), io::Error>(())
+//! ```
+//!
+//! ## Durability
+//!
+//! Each armed guard is one file in the journal directory, written to a temporary file, synced,
+//! and then atomically renamed into place. Leftover temporary files are thus incomplete records,
+//! for guards which never got armed, and [`Journal::recover()`] merely deletes them.
+//!
+//! Record files carry no type information: a [`Journal`] is thus typed by its [`Record`], and a
+//! given directory ought to be used for a single [`Record`] type (use a distinct directory, or
+//! an `enum` [`Record`], for different kinds of compensations).
+
+use super::*;
+use ::std::{
+    fs,
+    io,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicU64, Ordering},
+    vec::Vec,
+};
+
+const RECORD_EXTENSION: &str = "record";
+const TMP_EXTENSION: &str = "tmp";
+
+/// A compensation which can be persisted in a [`Journal`].
+pub
+trait Record : Sized {
+    /// Serializes the compensation, for it to be [`Self::decode()`]d by [`Journal::recover()`].
+    fn encode(&self) -> Vec<u8>;
+
+    fn decode(bytes: &[u8]) -> io::Result<Self>;
+
+    /// The rollback logic.
+    ///
+    /// Since it may end up being replayed (_e.g._, if the process is killed right after it ran,
+    /// but before its record got removed), it ought to be idempotent.
+    fn compensate(self) -> io::Result<()>;
+}
+
+/// A directory of pending `R` [`Record`]s: see [the module docs][self].
+pub
+struct Journal<R : Record> {
+    dir: PathBuf,
+    _record: PD<fn(R) -> R>,
+}
+
+impl<R : Record> ::core::fmt::Debug for Journal<R> {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        f   .debug_struct("Journal")
+            .field("dir", &self.dir)
+            .finish()
+    }
+}
+
+impl<R : Record> Journal<R> {
+    /// Opens (creating it if needed) the journal directory.
+    pub
+    fn open(dir: impl Into<PathBuf>) -> io::Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        Ok(Self { dir, _record: PD })
+    }
+
+    #[inline]
+    pub
+    fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Persists the `compensation`, and arms a [`JournalGuard`] for it.
+    pub
+    fn guard(&self, compensation: R) -> io::Result<JournalGuard<R>> {
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+        let nanos =
+            ::std::time::SystemTime::now()
+                .duration_since(::std::time::UNIX_EPOCH)
+                .map_or(0, |it| it.as_nanos())
+        ;
+        // Zero-padded, so that the lexicographic order is the chronological one.
+        let name = ::std::format!(
+            "{nanos:039}-{pid:010}-{count:020}",
+            pid = ::std::process::id(),
+            count = COUNTER.fetch_add(1, Ordering::Relaxed),
+        );
+        let tmp = self.dir.join(&name).with_extension(TMP_EXTENSION);
+        let path = self.dir.join(name).with_extension(RECORD_EXTENSION);
+        let write = || {
+            let mut file = fs::File::create(&tmp)?;
+            io::Write::write_all(&mut file, &compensation.encode())?;
+            file.sync_all()?;
+            fs::rename(&tmp, &path)?;
+            sync_dir(&self.dir);
+            Ok(())
+        };
+        write().inspect_err(|_: &io::Error| {
+            let _ = fs::remove_file(&tmp);
+        })?;
+        Ok(JournalGuard(SafeManuallyDrop::new(Pending { compensation, path })))
+    }
+
+    /// Replays, most recent first, the compensations of the guards which did not get to be
+    /// committed nor dropped (_e.g._, because of a crash), removing their records.
+    ///
+    /// Returns how many compensations were replayed. Stops at the first error, leaving the
+    /// remaining records in place.
+    ///
+    /// This is intended to be called at startup, before any new guard is created.
+    pub
+    fn recover(&self) -> io::Result<usize> {
+        let mut records = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            match path.extension().and_then(|it| it.to_str()) {
+                | Some(RECORD_EXTENSION) => records.push(path),
+                | Some(TMP_EXTENSION) => fs::remove_file(&path)?,
+                | _ => {},
+            }
+        }
+        records.sort_unstable_by(|a, b| b.cmp(a));
+        for path in &records {
+            R::decode(&fs::read(path)?)?.compensate()?;
+            remove_record(path)?;
+        }
+        Ok(records.len())
+    }
+}
+
+/// Makes a change to the entries of `dir` durable.
+///
+/// Best-effort: not all platforms support syncing a directory.
+fn sync_dir(dir: &Path) {
+    let _ = fs::File::open(dir).and_then(|dir| dir.sync_all());
+}
+
+/// Removes a record, durably, lest it reappear after a crash, and its compensation be replayed.
+fn remove_record(path: &Path) -> io::Result<()> {
+    fs::remove_file(path)?;
+    if let Some(dir) = path.parent() {
+        sync_dir(dir);
+    }
+    Ok(())
+}
+
+/// The journaled counterpart of a [`TxnGuard`][crate::transaction::TxnGuard]: runs its
+/// compensation when dropped, unless [`.commit()`][Self::commit]-ted, and, either way, removes its
+/// record from the [`Journal`].
+///
+/// Errors when dropped are reported to the [`try_drop`] error sinks, and the record is then kept,
+/// for [`Journal::recover()`] to retry.
+pub
+struct JournalGuard<R : Record>(
+    SafeManuallyDrop<Pending<R>, try_drop::Fallible<Self>>,
+);
+
+/// The owned contents of a [`JournalGuard`].
+struct Pending<R> {
+    compensation: R,
+    path: PathBuf,
+}
+
+impl<R : Record>
+    try_drop::TryDropManually<Pending<R>>
+for
+    JournalGuard<R>
+{
+    type Error = io::Error;
+
+    fn try_drop_manually(Pending { compensation, path }: Pending<R>) -> io::Result<()> {
+        compensation.compensate()?;
+        remove_record(&path)
+    }
+}
+
+impl<R : Record> JournalGuard<R> {
+    /// Disarms the guard, and removes its record.
+    ///
+    /// If the removal fails, the record is left behind, and a later [`Journal::recover()`] would
+    /// replay it.
+    pub
+    fn commit(self) -> io::Result<()> {
+        let Pending { compensation: _, path } = self.0.into_inner_defusing_impl_Drop();
+        remove_record(&path)
+    }
+
+    /// Runs the compensation right away, and removes its record; reporting errors to the caller
+    /// rather than to the error sinks.
+    pub
+    fn roll_back(self) -> io::Result<()> {
+        self.0.close()
+    }
+
+    /// The path of the record backing this guard.
+    #[inline]
+    pub
+    fn record_path(&self) -> &Path {
+        &self.0.path
+    }
+}
+
+impl<R : Record> ::core::ops::Deref for JournalGuard<R> {
+    type Target = R;
+
+    #[inline]
+    fn deref(&self) -> &R {
+        &self.0.compensation
+    }
+}
@@ -0,0 +1,99 @@
+//! A compile-time "must not be dropped" guard: [`Linear`].
+//!
+//! The drop hook of a [`Linear`] is a `const { panic!() }` block, which is only evaluated when the
+//! hook gets instantiated, that is, when the compiler emits the drop glue of a `Linear<T>`
+//! somewhere. Any code path which could implicitly drop one thus fails to build, and the only
+//! way out is to explicitly [`.into_inner()`][Linear::into_inner] it.
+//!
+//! ```rust
+//! use ::safe_manually_drop::linear::Linear;
+//!
+//! /// A protocol state which must be explicitly transitioned out of.
+//! struct Handshake { /* … */ }
+//!
+//! fn start() -> Linear<Handshake> {
+//!     Linear::new(Handshake { /* … */ })
+//! }
+//!
+//! fn finish(handshake: Linear<Handshake>) {
+//!     let Handshake { /* … */ } = handshake.into_inner();
+//! }
+//!
+//! finish(start()); // OK
+//! ```
+//!
+//! Whereas `let _ = start();` fails to compile, with an error along the lines of:
+//!
+//! ```text
+//! error[E0080]: evaluation of `<Linear<Handshake> as DropManually<…>>::drop_manually::{constant#0}` failed
+//!   …
+//!   the evaluated program panicked at 'a `Linear<_>` may be implicitly dropped: it must be …'
+//!   …
+//! note: the above error was encountered while instantiating `fn <Linear<Handshake> as …>::drop_manually`
+//! ```
+//!
+//! See the [runtime counterpart][crate::must_consume::MustConsume] for a more lenient approach.
+//!
+//! ## Caveats
+//!
+//! This is a post-monomorphization error, which comes with quite a few limitations:
+//!
+//!   - it is not reported by `cargo check`, only by `cargo build` (and the like);
+//!
+//!   - it does not point at the offending code, but at this crate (with a note about the
+//!     instantiation involved);
+//!
+//!   - code which is never instantiated (_e.g._, an uncalled generic function) is not checked;
+//!
+//!   - unwinding counts as a drop path: with `panic = "unwind"`, calling _any_ function while
+//!     owning a `Linear` (save for consuming it) is rejected, since said call could panic. This
+//!     is why no [`Deref`][::core::ops::Deref] is offered: access the value by consuming the
+//!     `Linear`, _e.g._, through [`.map()`][Linear::map];
+//!
+//!   - leaking it, _e.g._, through [`::core::mem::forget()`], is not caught.
+
+use super::*;
+
+/// A value which does not compile when it may be implicitly dropped: see
+/// [the module docs][self].
+pub
+struct Linear<T>(
+    SafeManuallyDrop<T, Self>,
+);
+
+impl<T> DropManually<T> for Linear<T> {
+    fn drop_manually(_: T) {
+        // Inline `const`s are generic over the surrounding generics: this is only evaluated once
+        // instantiated.
+        const {
+            panic!(
+                "a `Linear<_>` may be implicitly dropped: it must be `.into_inner()`-ed instead",
+            );
+        }
+    }
+}
+
+impl<T> Linear<T> {
+    #[inline]
+    pub
+    const
+    fn new(value: T) -> Self {
+        Self(SafeManuallyDrop::new(value))
+    }
+
+    /// The (only) way to get rid of a `Linear`.
+    #[inline]
+    pub
+    fn into_inner(self) -> T {
+        self.0.into_inner_defusing_impl_Drop()
+    }
+
+    /// Consumes the value, and wraps the result of `f` back.
+    ///
+    /// Note that, should `f` panic, the value it owns at that point is dropped normally.
+    #[inline]
+    pub
+    fn map<U>(self, f: impl FnOnce(T) -> U) -> Linear<U> {
+        Linear::new(f(self.into_inner()))
+    }
+}
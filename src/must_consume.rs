@@ -0,0 +1,110 @@
+//! A runtime drop bomb, for values which must be explicitly consumed: [`MustConsume`].
+//!
+//! ```rust ,should_panic
+//! use ::safe_manually_drop::must_consume::MustConsume;
+//!
+//! struct Request { /* … */ }
+//!
+//! impl Request {
+//!     fn send(self) { /* … */ }
+//! }
+//!
+//! struct RequestBuilder(MustConsume<Request>);
+//!
+//! impl RequestBuilder {
+//!     fn new() -> Self {
+//!         Self(MustConsume::new(Request { /* … */ }))
+//!     }
+//!
+//!     fn send(self) {
+//!         self.0.consume().send()
+//!     }
+//! }
+//!
+//! RequestBuilder::new().send(); // OK
+//! let _ = RequestBuilder::new(); // panics: forgot to `.send()` it!
+//! ```
+//!
+//! For a compile-time counterpart (with caveats), see [`Linear`][crate::linear::Linear].
+
+use super::*;
+use ::core::panic::Location;
+use context::{DropManuallyWithContext, SafeManuallyDropWithContext};
+
+/// Panics when dropped rather than [`.consume()`][Self::consume]d, mentioning where it was
+/// created.
+///
+/// Should that happen during unwinding, the process is aborted (double panic).
+pub
+struct MustConsume<T>(
+    SafeManuallyDropWithContext<T, Self>,
+);
+
+impl<T> DropManuallyWithContext<T> for MustConsume<T> {
+    type Context = &'static Location<'static>;
+
+    fn drop_manually_with_context(_: T, created_at: &'static Location<'static>) {
+        let type_name = ::core::any::type_name::<T>();
+        #[cfg(feature = "std")]
+        if ::std::thread::panicking() {
+            ::std::eprintln!(
+                "a `MustConsume<{type_name}>`, created at {created_at}, got dropped during \
+                unwinding: aborting.",
+            );
+            ::std::process::abort();
+        }
+        panic!(
+            "a `MustConsume<{type_name}>`, created at {created_at}, got dropped rather than \
+            `.consume()`d",
+        );
+    }
+}
+
+impl<T> MustConsume<T> {
+    /// Arms the drop bomb, recording the caller's location.
+    #[inline]
+    #[track_caller]
+    pub
+    fn new(value: T) -> Self {
+        Self(SafeManuallyDropWithContext::new(value, Location::caller()))
+    }
+
+    /// Defuses the drop bomb, yielding back the value.
+    #[inline]
+    pub
+    fn consume(self) -> T {
+        self.0.into_inner_defusing_impl_Drop().0
+    }
+
+    /// Where this was [`MustConsume::new()`]-ed.
+    #[inline]
+    pub
+    fn created_at(&self) -> &'static Location<'static> {
+        self.0.context()
+    }
+}
+
+impl<T> ::core::ops::Deref for MustConsume<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> ::core::ops::DerefMut for MustConsume<T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T : ::core::fmt::Debug> ::core::fmt::Debug for MustConsume<T> {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        f   .debug_struct("MustConsume")
+            .field("value", &**self)
+            .field("created_at", self.created_at())
+            .finish()
+    }
+}
@@ -0,0 +1,160 @@
+//! Configurable behavior for when a [`DropManually::drop_manually()`] hook panics.
+//!
+//! By default, the panic simply propagates, as with any other drop glue. Which means that if the
+//! hook panics while the [`SafeManuallyDrop`] was already being dropped because of some unwinding,
+//! the process shall abort (double panic).
+//!
+//! For cleanup logic talking to fallible subsystems, this can be tweaked on a per-hook basis, _via_
+//! [`DropManually::ON_PANIC`]:
+//!
+//! ```rust
+//! use ::safe_manually_drop::prelude::*;
+//!
+//! # struct Connection;
+//! # impl Connection { fn close(self) {} }
+//! #
+//! struct Client {
+//!     conn: SafeManuallyDrop<Connection, Self>,
+//! }
+//!
+//! impl DropManually<Connection> for Client {
+//!     const ON_PANIC: OnPanic = OnPanic::Abort;
+//!
+//!     fn drop_manually(conn: Connection) {
+//!         conn.close();
+//!     }
+//! }
+//! ```
+
+use super::*;
+
+/// Policy for when a [`DropManually::drop_manually()`] hook panics.
+///
+/// See [`DropManually::ON_PANIC`], and [the module docs][self] for more info.
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub
+enum OnPanic {
+    /// The panic propagates, as with any other drop glue (the default).
+    Propagate,
+
+    /// The process is aborted, with a message stating which hook panicked.
+    ///
+    /// This does not require `std` (the abort is achieved _via_ a double panic).
+    Abort,
+
+    /// The panic is caught (using [`::std::panic::catch_unwind()`]), and handed to the given
+    /// handler; such as [`report_to_stderr`].
+    #[cfg(feature = "std")]
+    #[cfg_attr(feature = "better-docs", doc(cfg(feature = "std")))]
+    CatchAndReport(fn(CaughtPanic)),
+}
+
+/// A panic from some [`DropManually::drop_manually()`] hook which an [`OnPanic::CatchAndReport`]
+/// policy has caught.
+#[cfg(feature = "std")]
+#[cfg_attr(feature = "better-docs", doc(cfg(feature = "std")))]
+#[derive(Debug)]
+pub
+struct CaughtPanic {
+    /// The payload returned by [`::std::panic::catch_unwind()`].
+    pub payload: ::std::boxed::Box<dyn ::core::any::Any + Send>,
+    /// The [`::core::any::type_name()`] of the `FieldTy`.
+    pub field_type_name: &'static str,
+    /// The [`::core::any::type_name()`] of the `ContainingType`.
+    pub containing_type_name: &'static str,
+}
+
+#[cfg(feature = "std")]
+impl CaughtPanic {
+    /// The panic message, when it is a string (the overwhelmingly common case).
+    pub
+    fn message(&self) -> Option<&str> {
+        self.payload.downcast_ref::<&'static str>().copied().or_else(|| {
+            self.payload.downcast_ref::<::std::string::String>().map(|s| &s[..])
+        })
+    }
+}
+
+/// A handler for [`OnPanic::CatchAndReport`] which prints the panic to stderr.
+#[cfg(feature = "std")]
+#[cfg_attr(feature = "better-docs", doc(cfg(feature = "std")))]
+pub
+fn report_to_stderr(caught: CaughtPanic) {
+    ::std::eprintln!(
+        "`<{} as DropManually<{}>>::drop_manually()` panicked: {}",
+        caught.containing_type_name,
+        caught.field_type_name,
+        caught.message().unwrap_or("Box<dyn Any>"),
+    );
+}
+
+/// Runs the hook according to its [`DropManually::ON_PANIC`] policy.
+#[inline]
+pub(crate)
+fn drop_manually<FieldTy, ContainingType : DropManually<FieldTy, Tag>, Tag>(owned: FieldTy) {
+    match ContainingType::ON_PANIC {
+        | OnPanic::Propagate => {
+            ContainingType::drop_manually(owned)
+        },
+        | OnPanic::Abort => {
+            let abort_on_unwind = AbortOnUnwind::<FieldTy, ContainingType>(PD);
+            ContainingType::drop_manually(owned);
+            ::core::mem::forget(abort_on_unwind);
+        },
+        #[cfg(feature = "std")]
+        | OnPanic::CatchAndReport(report) => {
+            let caught = ::std::panic::catch_unwind(::core::panic::AssertUnwindSafe(|| {
+                ContainingType::drop_manually(owned)
+            }));
+            if let Err(payload) = caught {
+                report(CaughtPanic {
+                    payload,
+                    field_type_name: ::core::any::type_name::<FieldTy>(),
+                    containing_type_name: ::core::any::type_name::<ContainingType>(),
+                });
+            }
+        },
+    }
+}
+
+/// Panicking while already unwinding aborts the process, even in `no_std` land.
+struct AbortOnUnwind<FieldTy, ContainingType>(PD<fn(FieldTy) -> ContainingType>);
+
+impl<FieldTy, ContainingType> Drop for AbortOnUnwind<FieldTy, ContainingType> {
+    fn drop(&mut self) {
+        panic!(
+            "`<{} as DropManually<{}>>::drop_manually()` panicked, \
+            with an `OnPanic::Abort` policy: aborting.",
+            ::core::any::type_name::<ContainingType>(),
+            ::core::any::type_name::<FieldTy>(),
+        );
+    }
+}
+
+/// Runs `f`, which owns a value taken out of a [`SafeManuallyDrop`], aborting should it panic:
+/// the value would otherwise escape its drop hook.
+pub(crate)
+fn abort_on_unwind<FieldTy, ContainingType, R>(method: &'static str, f: impl FnOnce() -> R) -> R {
+    let abort_on_unwind = AbortIfClosurePanics::<FieldTy, ContainingType>(method, PD);
+    let ret = f();
+    ::core::mem::forget(abort_on_unwind);
+    ret
+}
+
+struct AbortIfClosurePanics<FieldTy, ContainingType>(
+    &'static str,
+    PD<fn(FieldTy) -> ContainingType>,
+);
+
+impl<FieldTy, ContainingType> Drop for AbortIfClosurePanics<FieldTy, ContainingType> {
+    fn drop(&mut self) {
+        panic!(
+            "the closure given to `SafeManuallyDrop<{}, {}>::{}()` panicked, whilst owning the \
+            value away from its drop hook: aborting.",
+            ::core::any::type_name::<FieldTy>(),
+            ::core::any::type_name::<ContainingType>(),
+            self.0,
+        );
+    }
+}
@@ -0,0 +1,226 @@
+//! `Mutex`-like poisoning, but for any resource: [`PoisonCell`].
+//!
+//! [`PoisonCell::lock()`] lends the owned value out, as a [`Poisonable`] guard, which hands it
+//! back to the cell when dropped. If that happens during unwinding, the cell gets poisoned: the
+//! state of the value may be half-updated, so later [`lock()`][PoisonCell::lock]s yield an
+//! <code>[Err]\([Poisoned]\(guard\)\)</code>, through which it can be inspected, and repaired.
+//!
+//! ```rust
+//! use ::safe_manually_drop::poison::{PoisonCell, Poisoned};
+//!
+//! let accounts = PoisonCell::new([100, 0]);
+//! let _ = ::std::panic::catch_unwind(|| {
+//!     let mut accounts = accounts.lock().unwrap();
+//!     accounts[0] -= 50;
+//!     panic!("something went wrong before crediting the other account");
+//! });
+//! match accounts.lock() {
+//!     | Ok(_) => unreachable!(),
+//!     | Err(Poisoned(mut accounts)) => {
+//!         // Repair the state…
+//!         accounts[0] = 100;
+//!         // …and lift the poison.
+//!         accounts.clear_poison();
+//!     },
+//! }
+//! assert_eq!(*accounts.lock().unwrap(), [100, 0]);
+//! ```
+//!
+//! In `no_std` environments, the check for whether the thread is panicking is to be provided by
+//! the user: see [`PoisonCell::with_panic_check()`].
+//!
+//! A recovery hook, handed the owned value as it gets poisoned, and returning the one to be put
+//! back in the cell, can also be provided: see
+//! [`PoisonCell::with_recovery()`].
+
+use super::*;
+use ::core::cell::Cell;
+use context::{DropManuallyWithContext, SafeManuallyDropWithContext};
+
+/// A cell whose value gets poisoned when a [`Poisonable`] guard to it is dropped during
+/// unwinding: see [the module docs][self].
+///
+/// This is a single-threaded (`!Sync`) cell, usable in `no_std` environments.
+pub
+struct PoisonCell<T> {
+    value: Cell<Option<T>>,
+    poisoned: Cell<bool>,
+    is_panicking: fn() -> bool,
+    on_poison: Option<fn(T) -> T>,
+}
+
+/// Like for `Mutex`es, poisoning is what makes observing a value after a panic fine.
+#[cfg(feature = "std")]
+impl<T> ::std::panic::RefUnwindSafe for PoisonCell<T> {}
+
+#[cfg(feature = "std")]
+impl<T> ::std::panic::UnwindSafe for PoisonCell<T> {}
+
+/// The error of a poisoned [`PoisonCell`]: grants access to its value nonetheless.
+#[derive(Debug)]
+pub
+struct Poisoned<T>(pub T);
+
+impl<T> Poisoned<T> {
+    #[inline]
+    pub
+    fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> PoisonCell<T> {
+    /// Uses [`::std::thread::panicking()`] to detect unwinding.
+    #[cfg(feature = "std")]
+    #[cfg_attr(feature = "better-docs", doc(cfg(feature = "std")))]
+    #[inline]
+    pub
+    const
+    fn new(value: T) -> Self {
+        Self::with_panic_check(value, ::std::thread::panicking)
+    }
+
+    /// Uses the given `is_panicking` function to detect unwinding (_e.g._, for `no_std`
+    /// environments).
+    #[inline]
+    pub
+    const
+    fn with_panic_check(value: T, is_panicking: fn() -> bool) -> Self {
+        Self {
+            value: Cell::new(Some(value)),
+            poisoned: Cell::new(false),
+            is_panicking,
+            on_poison: None,
+        }
+    }
+
+    /// Sets a recovery hook, to be handed the (owned) value whenever a [`Poisonable`] guard to it
+    /// is dropped during unwinding, _e.g._, to roll back some half-done update; the value it
+    /// returns is the one put back in the cell.
+    ///
+    /// The cell still gets poisoned: lifting it is up to later users.
+    ///
+    /// The hook is a plain `fn` pointer (so as to keep a single `PoisonCell<T>` type), so it
+    /// cannot capture any state: keep whatever it needs within the `T` itself.
+    ///
+    /// Since the hook runs during unwinding, should it panic, the process is aborted.
+    ///
+    /// ```rust
+    /// use ::safe_manually_drop::poison::PoisonCell;
+    ///
+    /// let cell = PoisonCell::new(vec![1, 2, 3]).with_recovery(|_half_done| vec![]);
+    /// let _ = ::std::panic::catch_unwind(|| {
+    ///     let _v = cell.lock().unwrap();
+    ///     panic!();
+    /// });
+    /// assert!(cell.lock().unwrap_err().0.is_empty());
+    /// ```
+    #[inline]
+    pub
+    fn with_recovery(self, on_poison: fn(T) -> T) -> Self {
+        Self {
+            on_poison: Some(on_poison),
+            ..self
+        }
+    }
+
+    /// Lends the value out, as a [`Poisonable`] guard.
+    ///
+    /// # Panics
+    ///
+    /// If the value is already lent out.
+    pub
+    fn lock(&self) -> Result<Poisonable<'_, T>, Poisoned<Poisonable<'_, T>>> {
+        let value = self.value.take().expect("`PoisonCell` already locked");
+        let guard = Poisonable(SafeManuallyDropWithContext::new(value, self));
+        if self.is_poisoned() {
+            Err(Poisoned(guard))
+        } else {
+            Ok(guard)
+        }
+    }
+
+    #[inline]
+    pub
+    fn is_poisoned(&self) -> bool {
+        self.poisoned.get()
+    }
+
+    #[inline]
+    pub
+    fn clear_poison(&self) {
+        self.poisoned.set(false);
+    }
+
+    /// # Panics
+    ///
+    /// If the value is lent out (only possible if some [`Poisonable`] got leaked).
+    pub
+    fn into_inner(self) -> Result<T, Poisoned<T>> {
+        let value = self.value.take().expect("`PoisonCell` still locked");
+        if self.is_poisoned() {
+            Err(Poisoned(value))
+        } else {
+            Ok(value)
+        }
+    }
+}
+
+/// The guard of a [`PoisonCell::lock()`], [`Deref`][::core::ops::Deref]-ing to the value.
+///
+/// Hands the owned value back to the cell when dropped, poisoning it if that happens during
+/// unwinding.
+pub
+struct Poisonable<'cell, T : 'cell>(
+    SafeManuallyDropWithContext<T, Self>,
+);
+
+impl<'cell, T : 'cell>
+    DropManuallyWithContext<T>
+for
+    Poisonable<'cell, T>
+{
+    type Context = &'cell PoisonCell<T>;
+
+    #[inline]
+    fn drop_manually_with_context(mut value: T, cell: &'cell PoisonCell<T>) {
+        if (cell.is_panicking)() {
+            if let Some(on_poison) = cell.on_poison {
+                value = on_poison(value);
+            }
+            cell.poisoned.set(true);
+        }
+        cell.value.set(Some(value));
+    }
+}
+
+impl<T> Poisonable<'_, T> {
+    /// Lifts the poison of the cell, _e.g._, once the value has been repaired.
+    #[inline]
+    pub
+    fn clear_poison(&self) {
+        self.0.context().clear_poison();
+    }
+}
+
+impl<T> ::core::ops::Deref for Poisonable<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> ::core::ops::DerefMut for Poisonable<'_, T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T : ::core::fmt::Debug> ::core::fmt::Debug for Poisonable<'_, T> {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        T::fmt(self, f)
+    }
+}
@@ -0,0 +1,91 @@
+//! Ready-made `ContainingType`s, for a [`SafeManuallyDrop<FieldTy, ContainingType>`] to get a
+//! stock drop hook without having to define a type for it.
+//!
+//! ```rust
+//! use ::safe_manually_drop::{policy, SafeManuallyDrop};
+//!
+//! /// Must be handed back to the C library rather than freed by Rust.
+//! struct Handle(SafeManuallyDrop<Box<u8>, policy::Leak>);
+//!
+//! let _ = Handle(SafeManuallyDrop::new(Box::new(42)));
+//! ```
+//!
+//! They implement [`DropManually`] for every `FieldTy` (and `Tag`).
+
+use super::*;
+
+/// [`::core::mem::forget()`]s the value.
+pub
+enum Leak {}
+
+impl<FieldTy, Tag> DropManually<FieldTy, Tag> for Leak {
+    #[inline]
+    fn drop_manually(value: FieldTy) {
+        ::core::mem::forget(value);
+    }
+}
+
+/// Aborts the process, with a message naming the `FieldTy`.
+///
+/// For values which must never be implicitly dropped, not even during unwinding.
+///
+/// This does not require `std` (as with [`OnPanic::Abort`], _via_ a double panic).
+pub
+enum Abort {}
+
+impl<FieldTy, Tag> DropManually<FieldTy, Tag> for Abort {
+    const ON_PANIC: OnPanic = OnPanic::Abort;
+
+    fn drop_manually(_: FieldTy) {
+        panic!(
+            "a `SafeManuallyDrop<{}, policy::Abort>` got dropped",
+            ::core::any::type_name::<FieldTy>(),
+        );
+    }
+}
+
+/// Panics, with a message naming the `FieldTy`.
+///
+/// Mainly intended as a debugging aid, for values which are expected to be explicitly consumed.
+/// Note that, if dropped during unwinding, the process aborts (double panic).
+pub
+enum PanicIfDropped {}
+
+impl<FieldTy, Tag> DropManually<FieldTy, Tag> for PanicIfDropped {
+    fn drop_manually(_: FieldTy) {
+        panic!(
+            "a `SafeManuallyDrop<{}, policy::PanicIfDropped>` got dropped",
+            ::core::any::type_name::<FieldTy>(),
+        );
+    }
+}
+
+/// Prints to stderr a message naming the `FieldTy`, and then drops the value normally.
+#[cfg(feature = "std")]
+#[cfg_attr(feature = "better-docs", doc(cfg(feature = "std")))]
+pub
+enum Log {}
+
+#[cfg(feature = "std")]
+impl<FieldTy, Tag> DropManually<FieldTy, Tag> for Log {
+    fn drop_manually(value: FieldTy) {
+        ::std::eprintln!(
+            "dropping a `SafeManuallyDrop<{}, policy::Log>`",
+            ::core::any::type_name::<FieldTy>(),
+        );
+        drop(value);
+    }
+}
+
+/// Drops the value normally, as if it were not wrapped in a [`SafeManuallyDrop`].
+///
+/// Handy as a placeholder, or for a generic `ContainingType` parameter.
+pub
+enum DropNormally {}
+
+impl<FieldTy, Tag> DropManually<FieldTy, Tag> for DropNormally {
+    #[inline]
+    fn drop_manually(value: FieldTy) {
+        drop(value);
+    }
+}
@@ -0,0 +1,246 @@
+//! Crate not intended for direct use.
+//! Use https:://docs.rs/safe-manually-drop instead.
+// Templated by `cargo-generate` using https://github.com/danielhenrymantilla/proc-macro-template
+#![allow(nonstandard_style, unused_imports, unused_braces, clippy::redundant_pattern)]
+
+use ::core::{
+    mem,
+    ops::Not as _,
+};
+use ::proc_macro::{
+    TokenStream,
+};
+use ::proc_macro2::{
+    Span,
+    TokenStream as TokenStream2,
+    TokenTree as TT,
+};
+use ::quote::{
+    format_ident,
+    quote_spanned,
+    ToTokens,
+};
+use ::syn::{*,
+    ext::IdentExt as _,
+    parse::{Parse, Parser, ParseStream},
+    punctuated::Punctuated,
+    Result, // Explicitly shadow it
+    spanned::Spanned,
+};
+use self::utils::default_to_mixed_site_span::{
+    quote, parse_quote, SpanLocationExt as _,
+};
+
+#[macro_use]
+#[path = "utils/_mod.rs"]
+mod utils;
+use utils::CombineOrInsert as _;
+
+mod args;
+
+#[proc_macro_attribute] pub
+fn drop_manually(
+    args: TokenStream,
+    input: TokenStream,
+) -> TokenStream
+{
+    drop_manually_impl(args.into(), input.into())
+    //  .map(|ret| { println!("{}", ret); ret })
+        .unwrap_or_else(|err| {
+            let mut errors =
+                err .into_iter()
+                    .map(|err| Error::new(
+                        err.span(),
+                        format_args!("`#[safe_manually_drop::drop_manually]`: {}", err),
+                    ))
+            ;
+            let mut err = errors.next().unwrap();
+            errors.for_each(|cur| err.combine(cur));
+            err.to_compile_error()
+        })
+        .into()
+}
+
+fn drop_manually_impl(
+    args: TokenStream2,
+    input: TokenStream2,
+) -> Result<TokenStream2>
+{
+    if let Some(tt) = args.into_iter().next() {
+        return Err(Error::new_spanned(tt, "\
+            unexpected argument: on the `struct` itself, use a bare `#[drop_manually]`, \
+            and then annotate the fields with `#[drop_manually(path::to::hook)]`.\
+        "));
+    }
+    let mut input: DeriveInput = parse2(input)?;
+    let DeriveInput {
+        vis,
+        ident: StructName @ _,
+        generics,
+        data,
+        ..
+    } = &mut input;
+    let DataStruct { fields, .. } = match *data {
+        | Data::Struct(ref mut it) => it,
+        | Data::Enum(DataEnum { enum_token: token::Enum { span, .. }, .. })
+        | Data::Union(DataUnion { union_token: token::Union { span, .. }, .. })
+        => {
+            return Err(Error::new(span, "expected a `struct`"));
+        },
+    };
+    let (IntroGenerics @ _, FwdGenerics @ _, where_clauses) = generics.split_for_impl();
+
+    let members: Vec<Member> =
+        fields
+            .iter()
+            .enumerate()
+            .map(|(i, field)| match &field.ident {
+                | Some(ident) => Member::Named(ident.clone()),
+                | None => Member::Unnamed(i.into()),
+            })
+            .collect()
+    ;
+
+    let mut impls = quote!();
+    // Hooks needing `&`-access to sibling fields, to be called from an `impl Drop` of the
+    // containing `struct`.
+    let mut sibling_aware_hooks = vec![];
+    for (field, member) in fields.iter_mut().zip(&members) {
+        let mut args = None;
+        let mut error = None::<Error>;
+        field.attrs.retain(|attr| match args::FieldArgs::from_attr(attr) {
+            | Ok(None) => true,
+            | Ok(Some(_)) if args.is_some() => {
+                let err = Error::new_spanned(attr, "duplicate `#[drop_manually(…)]` attribute");
+                error.combine_or_insert(err);
+                false
+            },
+            | Ok(Some(it)) => {
+                args = Some(it);
+                false
+            },
+            | Err(err) => {
+                error.combine_or_insert(err);
+                false
+            },
+        });
+        if let Some(error) = error {
+            return Err(error);
+        }
+        let Some(args::FieldArgs { hook, hook_args }) = args else { continue };
+        let FieldTy @ _ = &mem::replace(&mut field.ty, parse_quote!(_));
+        if let Some(hook_args) = hook_args {
+            field.ty = parse_quote!(
+                ::safe_manually_drop::SiblingAwareField<#FieldTy>
+            );
+            sibling_aware_hooks.push((member, hook, hook_args));
+            continue;
+        }
+        // A per-field `Tag`, so that several fields may share a `FieldTy`.
+        let Tag @ _ = format_ident!(
+            "__drop_manually_Tag_{}_{}",
+            StructName,
+            match member {
+                | Member::Named(ident) => ident.unraw().to_string(),
+                | Member::Unnamed(idx) => idx.index.to_string(),
+            },
+            span = Span::call_site(),
+        );
+        impls.extend(quote!(
+            #[doc(hidden)]
+            #[allow(nonstandard_style)]
+            #vis
+            enum #Tag {}
+        ));
+        field.ty = parse_quote!(
+            ::safe_manually_drop::SafeManuallyDrop<#FieldTy, Self, #Tag>
+        );
+        let call_hook = quote_spanned!(hook.span_location()=>
+            #hook(field)
+        );
+        impls.extend(quote!(
+            impl #IntroGenerics
+                ::safe_manually_drop::DropManually<#FieldTy, #Tag>
+            for
+                #StructName #FwdGenerics
+            #where_clauses
+            {
+                #[inline]
+                fn drop_manually(field: #FieldTy) {
+                    #call_hook
+                }
+            }
+        ));
+    }
+
+    if sibling_aware_hooks.is_empty().not() {
+        let mut error = None::<Error>;
+        let mut take_fields = quote!();
+        let mut arm_hooks = vec![];
+        for (i, &(member, ref hook, ref hook_args)) in sibling_aware_hooks.iter().enumerate() {
+            let field = format_ident!("field_{}", i);
+            let args = hook_args.args.iter().map(|arg| match arg {
+                | args::HookArg::Owned(_) => quote!(#field),
+                | args::HookArg::Sibling(ampersand, sibling) => {
+                    if members.contains(sibling).not() {
+                        error.combine_or_insert(Error::new_spanned(sibling, "no such field"));
+                    } else if sibling_aware_hooks.iter().any(|&(it, ..)| it == sibling) {
+                        error.combine_or_insert(Error::new_spanned(sibling, "\
+                            cannot refer to a field which has, itself, a sibling-aware \
+                            `#[drop_manually(…)]` hook (it gets moved out when dropped)\
+                        "));
+                    }
+                    quote!(#ampersand this.#sibling)
+                },
+            });
+            let call_hook = quote_spanned!(hook.span_location()=>
+                #hook(#(#args),*)
+            );
+            take_fields.extend(quote!(
+                let #field = ::safe_manually_drop::SiblingAwareField::__take(&mut self.#member);
+            ));
+            let guard = format_ident!("_hook_{}", i);
+            arm_hooks.push(quote!(
+                let #guard = ::safe_manually_drop::defer::Defer::new(move || {
+                    #call_hook;
+                });
+            ));
+        }
+        // Every field is armed before any hook runs, so that, should one panic, the later ones
+        // still get to run. Guards are dropped in reverse order, hence the `.reverse()`.
+        arm_hooks.reverse();
+        let call_hooks = quote!(
+            #take_fields
+            #[allow(unused_variables)]
+            let this = &*self;
+            #(#arm_hooks)*
+        );
+        if let Some(error) = error {
+            return Err(error);
+        }
+        impls.extend(quote!(
+            impl #IntroGenerics
+                ::safe_manually_drop::ඞ::core::ops::Drop
+            for
+                #StructName #FwdGenerics
+            #where_clauses
+            {
+                #[inline]
+                fn drop(&mut self) {
+                    #call_hooks
+                }
+            }
+        ));
+    }
+
+    if impls.is_empty() {
+        return Err(Error::new(StructName.span(), "\
+            no field annotated with `#[drop_manually(path::to::hook)]`\
+        "));
+    }
+    Ok(quote!(
+        #input
+
+        #impls
+    ))
+}
@@ -0,0 +1,100 @@
+use super::*;
+
+/// `#[drop_manually(<args>)]`, on a field.
+pub(crate)
+struct FieldArgs {
+    pub(crate) hook: Path,
+    /// `hook(_, &sibling, …)`.
+    pub(crate) hook_args: Option<HookArgs>,
+}
+
+impl Parse for FieldArgs {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        let hook = input.parse()?;
+        let hook_args = if input.peek(token::Paren) {
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        let _: Option<Token![,]> = input.parse()?;
+        if input.is_empty().not() {
+            return Err(input.error("unexpected extraneous input"));
+        }
+        Ok(Self {
+            hook,
+            hook_args,
+        })
+    }
+}
+
+impl FieldArgs {
+    /// `Ok(None)` if the attribute is not ours.
+    pub(crate)
+    fn from_attr(attr: &Attribute) -> Result<Option<Self>> {
+        if attr.path().is_ident("drop_manually").not() {
+            return Ok(None);
+        }
+        match &attr.meta {
+            | Meta::List(list) => list.parse_args().map(Some),
+            | _ => Err(Error::new_spanned(
+                attr,
+                "expected a path to the drop hook: `#[drop_manually(path::to::hook)]`",
+            )),
+        }
+    }
+}
+
+/// `(_, &sibling, …)`
+pub(crate)
+struct HookArgs {
+    pub(crate) args: Punctuated<HookArg, Token![,]>,
+}
+
+pub(crate)
+enum HookArg {
+    /// `_`: the owned field itself.
+    Owned(Token![_]),
+    /// `&sibling`, or `&0` for a tuple struct.
+    Sibling(Token![&], Member),
+}
+
+impl Parse for HookArgs {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        let contents;
+        let parens = parenthesized!(contents in input);
+        let args = Punctuated::<HookArg, Token![,]>::parse_terminated(&contents)?;
+        match args.iter().filter(|arg| matches!(arg, HookArg::Owned(_))).nth(1) {
+            | Some(HookArg::Owned(underscore)) => return Err(Error::new_spanned(
+                underscore,
+                "the owned field (`_`) can only be passed once",
+            )),
+            | _ if args.iter().any(|arg| matches!(arg, HookArg::Owned(_))).not() => {
+                return Err(Error::new(
+                    parens.span.join(),
+                    "missing `_` placeholder for the owned field, as in `hook(_, &sibling)`",
+                ));
+            },
+            | _ => {},
+        }
+        Ok(Self {
+            args,
+        })
+    }
+}
+
+impl Parse for HookArg {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        let peeker = input.lookahead1();
+        match () {
+            | _case if peeker.peek(Token![_]) => Ok(Self::Owned(input.parse()?)),
+            | _case if peeker.peek(Token![&]) => {
+                let ampersand = input.parse()?;
+                if input.peek(Token![mut]) {
+                    return Err(input.error("sibling fields can only be accessed through `&`"));
+                }
+                Ok(Self::Sibling(ampersand, input.parse()?))
+            },
+            | _default => Err(peeker.error()),
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use super::*;
+
+#[macro_use]
+pub(crate) mod default_to_mixed_site_span {
+    #![allow(unused)]
+    /// ```rust ,ignore
+    /// quote!();
+    /// ```
+    macro_rules! quote {( $($tt:tt)* ) => (
+        ::quote::quote_spanned! {::proc_macro2::Span::mixed_site()=>
+            $($tt)*
+        }
+    )}
+    pub(crate) use quote;
+
+    /// ```rust ,ignore
+    /// parse_quote!();
+    /// ```
+    macro_rules! parse_quote {( $($tt:tt)* ) => (
+        ::syn::parse_quote_spanned! {::proc_macro2::Span::mixed_site()=>
+            $($tt)*
+        }
+    )}
+    pub(crate) use parse_quote;
+
+    /// Using span of user input tokens is nice for diagnostics to be properly *located at*
+    /// the proper code.
+    ///
+    /// However, there is a "pit of failure" / footgun wherein using `.span()` for this purpose
+    /// (e.g. in `{parse_,}quote_spanned!` invocations) is too strong, since it also pulls in
+    /// caller-code / user-code *hygiene*, which is excessive, very rarely intended.
+    pub(crate) trait SpanLocationExt : ::syn::spanned::Spanned {
+        fn span_location(&self) -> ::proc_macro2::Span {
+            ::proc_macro2::Span::mixed_site().located_at(self.span())
+        }
+    }
+    impl<T : ::syn::spanned::Spanned> SpanLocationExt for T {}
+}
+
+pub(crate)
+trait CombineOrInsert {
+    fn combine_or_insert(&mut self, err: Error);
+}
+
+impl CombineOrInsert for Option<Error> {
+    fn combine_or_insert(&mut self, err: Error) {
+        match self {
+            | Some(errors) => errors.combine(err),
+            | None => *self = Some(err),
+        }
+    }
+}
@@ -0,0 +1,139 @@
+//! All-or-nothing multi-step setups: [`Saga`], a stack of compensating actions.
+//!
+//! Each successful step [`.push()`][Saga::push]es its compensation: an undo function, along with
+//! the owned data it shall be fed. Should the [`Saga`] then be dropped before being
+//! [`.complete()`][Saga::complete]d (_e.g._, because of some `?` early return, or some panic), the
+//! compensations are run, in reverse order.
+//!
+//! ```rust
+//! use ::safe_manually_drop::saga::Saga;
+//! use ::std::{fs, io, path::PathBuf};
+//!
+//! fn setup(root: PathBuf) -> io::Result<()> {
+//!     fs::create_dir(&root)?;
+//!     let saga = Saga::new().push(root.clone(), |root| fs::remove_dir(root).unwrap());
+//!
+//!     let config = root.join("config.toml");
+//!     fs::write(&config, "")?;
+//!     let saga = saga.push(config, |config| fs::remove_file(config).unwrap());
+//!
+//!     register(&root)?; // on error, removes `config.toml`, and then `root`.
+//!
+//!     saga.complete();
+//!     Ok(())
+//! }
+//! # fn register(_: &PathBuf) -> io::Result<()> { Ok(()) }
+//! ```
+//!
+//! Since the list of steps is tracked at the type level, this works in `no_std` environments (no
+//! allocations involved). The flip side is that each [`.push()`][Saga::push] changes the type of
+//! the [`Saga`], so it cannot collect steps in a loop: the number of steps has to be known at
+//! compile time.
+#![cfg_attr(feature = "alloc", doc = "\
+    For a number of steps only known at runtime, see [`DeferStack`][crate::defer::DeferStack].\
+")]
+//!
+//! An existing [`DropManually`] impl can directly be used as an undo function, since
+//! <code>\<ContainingType as [DropManually]\<T\>\>::drop_manually</code> is a `fn(T)`. Note
+//! that calling it directly like this bypasses the [`DropManually::ON_PANIC`] policy of said impl.
+
+use super::*;
+
+/// A stack of compensating actions, run in reverse order if dropped before being
+/// [`.complete()`][Self::complete]d.
+///
+/// See [the module docs][self] for more info.
+pub
+struct Saga<Steps : Compensate = ()>(
+    SafeManuallyDrop<Steps, Self>,
+);
+
+/// The type-level list of compensations of a [`Saga`].
+///
+/// This is an implementation detail, of sorts: it is only public so as to be nameable.
+pub
+trait Compensate {
+    /// Runs the compensations, most recent first.
+    fn compensate(self);
+}
+
+impl Compensate for () {
+    #[inline]
+    fn compensate(self) {}
+}
+
+/// A compensation, on top of the `Prev`ious ones: see [`Saga::push()`].
+pub
+struct Step<Prev, T, Undo : FnOnce(T)> {
+    prev: Prev,
+    data: T,
+    undo: Undo,
+}
+
+/// A [`Saga::push_fn()`] compensation.
+pub
+type FnStep<Prev, Undo> = Step<Prev, Undo, fn(Undo)>;
+
+impl<Prev : Compensate, T, Undo : FnOnce(T)> Compensate for Step<Prev, T, Undo> {
+    #[inline]
+    fn compensate(self) {
+        let Self { prev, data, undo } = self;
+        // So that, even if `undo` panics, the previous compensations still run.
+        let _prev = Saga(SafeManuallyDrop::new(prev));
+        undo(data);
+    }
+}
+
+impl<Steps : Compensate> DropManually<Steps> for Saga<Steps> {
+    #[inline]
+    fn drop_manually(steps: Steps) {
+        steps.compensate();
+    }
+}
+
+impl Saga {
+    #[inline]
+    pub
+    const
+    fn new() -> Self {
+        Self(SafeManuallyDrop::new(()))
+    }
+}
+
+impl Default for Saga {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Steps : Compensate> Saga<Steps> {
+    /// Registers the compensation of a successful step: `undo(data)`.
+    #[inline]
+    pub
+    fn push<T, Undo : FnOnce(T)>(self, data: T, undo: Undo) -> Saga<Step<Steps, T, Undo>> {
+        let prev = self.0.into_inner_defusing_impl_Drop();
+        Saga(SafeManuallyDrop::new(Step { prev, data, undo }))
+    }
+
+    /// Registers the compensation of a successful step, as a mere closure.
+    #[inline]
+    pub
+    fn push_fn<Undo : FnOnce()>(self, undo: Undo) -> Saga<FnStep<Steps, Undo>> {
+        self.push(undo, |undo| undo())
+    }
+
+    /// Marks the saga as successful: the compensations are discarded, without being run.
+    #[inline]
+    pub
+    fn complete(self) {
+        drop(self.0.into_inner_defusing_impl_Drop());
+    }
+
+    /// Runs the compensations right away (same as dropping the saga, but more explicit).
+    #[inline]
+    pub
+    fn abort(self) {
+        drop(self);
+    }
+}
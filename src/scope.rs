@@ -0,0 +1,156 @@
+//! Leak-proof hooks: [`scope()`], and its [`ScopedGuard`]s.
+//!
+//! Plain RAII cannot guarantee that drop logic runs: a guard may be [`::core::mem::forget()`]-ten
+//! (or otherwise leaked, _e.g._, through an `Rc` cycle). For critical cleanup, [`scope()`] offers
+//! a hard guarantee instead: the [`Scope`] keeps track of the registered hooks, and, when the
+//! scope ends, runs those whose guard got leaked.
+//!
+//! ```rust
+//! use ::core::cell::RefCell;
+//!
+//! let log = RefCell::new(vec![]);
+//! ::safe_manually_drop::scope(|s| {
+//!     let a = s.guard("a", |it| log.borrow_mut().push(it));
+//!     let b = s.guard("b", |it| log.borrow_mut().push(it));
+//!     drop(a); // runs the hook, as usual.
+//!     ::core::mem::forget(b); // oh no!
+//!     assert_eq!(*log.borrow(), ["a"]);
+//! });
+//! // The scope caught up with the leaked `b`.
+//! assert_eq!(log.into_inner(), ["a", "b"]);
+//! ```
+//!
+//! Since leaked guards are detected at the end of the scope, their hooks run then, rather than at
+//! the point of leakage. Leftover hooks run in reverse registration order, even when the scope is
+//! exited through unwinding, or when one of them panics.
+
+use super::*;
+use ::alloc::{rc::Rc, vec::Vec};
+use ::core::cell::{Ref, RefCell, RefMut};
+
+/// Runs `f` with a [`Scope`], within which [`ScopedGuard`]s can be created, and which runs, when
+/// it ends, the hooks of the guards which have been leaked.
+///
+/// See [the module docs][self] for more info.
+///
+/// Like with [`::std::thread::scope()`], the guards may borrow anything which outlives the call
+/// (`'env`).
+pub
+fn scope<'env, R>(f: impl for<'scope> FnOnce(&'scope Scope<'scope, 'env>) -> R) -> R {
+    let scope = Scope {
+        slots: SafeManuallyDrop::new(RefCell::new(Vec::new())),
+        _scope: PD,
+        _env: PD,
+    };
+    f(&scope)
+}
+
+/// The handle to create [`ScopedGuard`]s with: see [`scope()`].
+pub
+struct Scope<'scope, 'env : 'scope> {
+    slots: SafeManuallyDrop<RefCell<Vec<Rc<dyn Slot + 'env>>>, RunLeftovers>,
+    /// Both lifetimes are invariant, lest a guard be able to outlive the scope.
+    _scope: PD<&'scope mut &'scope ()>,
+    _env: PD<&'env mut &'env ()>,
+}
+
+/// The shared storage of a [`ScopedGuard`]'s value and hook.
+type SlotOf<T, F> = RefCell<Option<(T, F)>>;
+
+trait Slot {
+    fn run_hook(&self);
+}
+
+impl<T, F : FnOnce(T)> Slot for SlotOf<T, F> {
+    fn run_hook(&self) {
+        // `take()` first, so as not to hold the borrow while running the hook.
+        let contents = self.take();
+        if let Some((value, hook)) = contents {
+            hook(value);
+        }
+    }
+}
+
+/// The drop logic of a [`Scope`]'s slots.
+///
+/// (Not `Scope` itself, since its `'scope` is still borrowed when it gets dropped.)
+enum RunLeftovers {}
+
+impl<'env> DropManually<RefCell<Vec<Rc<dyn Slot + 'env>>>> for RunLeftovers {
+    fn drop_manually(slots: RefCell<Vec<Rc<dyn Slot + 'env>>>) {
+        // Guards may no longer be around, so whatever is left in a slot belongs to a leaked one.
+        let mut slots = slots.into_inner();
+        while let Some(slot) = slots.pop() {
+            // So that, even if this hook panics, the remaining ones still run.
+            let rest = RefCell::new(::core::mem::take(&mut slots));
+            let rest = SafeManuallyDrop::<_, RunLeftovers>::new(rest);
+            slot.run_hook();
+            slots = rest.into_inner_defusing_impl_Drop().into_inner();
+        }
+    }
+}
+
+impl<'scope, 'env> Scope<'scope, 'env> {
+    /// Arms `hook(value)`, to be run when the returned guard is dropped, or, if it is leaked,
+    /// when the scope ends.
+    pub
+    fn guard<T, F>(&self, value: T, hook: F) -> ScopedGuard<'scope, T, F>
+    where
+        T : 'env,
+        F : 'env + FnOnce(T),
+    {
+        let slot = Rc::new(RefCell::new(Some((value, hook))));
+        let mut slots = self.slots.borrow_mut();
+        if slots.len() == slots.capacity() {
+            // Amortized pruning of the slots of guards which are gone.
+            slots.retain(|slot| Rc::strong_count(slot) > 1);
+        }
+        slots.push(slot.clone());
+        ScopedGuard(SafeManuallyDrop::new(slot), PD)
+    }
+}
+
+/// A guard created by [`Scope::guard()`], running its hook when dropped, or, if leaked, when the
+/// [`scope()`] ends.
+///
+/// Since its value is shared with the [`Scope`], it is accessed through
+/// [`.borrow()`][Self::borrow] and [`.borrow_mut()`][Self::borrow_mut].
+pub
+struct ScopedGuard<'scope, T : 'scope, F : 'scope + FnOnce(T)>(
+    SafeManuallyDrop<Rc<SlotOf<T, F>>, Self>,
+    PD<&'scope mut &'scope ()>,
+);
+
+impl<'scope, T : 'scope, F : 'scope + FnOnce(T)>
+    DropManually<Rc<SlotOf<T, F>>>
+for
+    ScopedGuard<'scope, T, F>
+{
+    #[inline]
+    fn drop_manually(slot: Rc<SlotOf<T, F>>) {
+        slot.run_hook();
+    }
+}
+
+impl<'scope, T : 'scope, F : 'scope + FnOnce(T)> ScopedGuard<'scope, T, F> {
+    #[inline]
+    pub
+    fn borrow(&self) -> Ref<'_, T> {
+        Ref::map(self.0.borrow(), |contents| &contents.as_ref().unwrap().0)
+    }
+
+    #[inline]
+    pub
+    fn borrow_mut(&mut self) -> RefMut<'_, T> {
+        RefMut::map(self.0.borrow_mut(), |contents| &mut contents.as_mut().unwrap().0)
+    }
+
+    /// Disarms the guard (and its registration in the [`Scope`]), handing back its value.
+    #[inline]
+    pub
+    fn defuse(self) -> T {
+        let slot = self.0.into_inner_defusing_impl_Drop();
+        let contents = slot.take();
+        contents.unwrap().0
+    }
+}
@@ -0,0 +1,170 @@
+//! [`ScopeGuard`]: a [`Defer`][crate::defer::Defer] which owns some `State`, and which only runs
+//! according to some [`Strategy`].
+//!
+//! ```rust
+//! use ::safe_manually_drop::scope_guard::ScopeGuard;
+//!
+//! let mut log = vec![];
+//! {
+//!     let mut log = ScopeGuard::new(&mut log, |log| log.push("cleanup"));
+//!     log.push("work");
+//! }
+//! assert_eq!(log, ["work", "cleanup"]);
+//! ```
+
+use super::*;
+
+/// When the `on_drop` closure of a [`ScopeGuard`] is to run.
+pub
+trait Strategy {
+    /// Queried when the [`ScopeGuard`] is dropped.
+    fn should_run() -> bool;
+}
+
+/// The `on_drop` closure always runs (the default).
+pub
+enum Always {}
+
+impl Strategy for Always {
+    #[inline]
+    fn should_run() -> bool {
+        true
+    }
+}
+
+/// The `on_drop` closure only runs on normal scope exit, _i.e._, not when unwinding.
+#[cfg(feature = "std")]
+#[cfg_attr(feature = "better-docs", doc(cfg(feature = "std")))]
+pub
+enum OnSuccess {}
+
+#[cfg(feature = "std")]
+impl Strategy for OnSuccess {
+    #[inline]
+    fn should_run() -> bool {
+        !::std::thread::panicking()
+    }
+}
+
+/// The `on_drop` closure only runs when unwinding, such as to roll back some partial work.
+#[cfg(feature = "std")]
+#[cfg_attr(feature = "better-docs", doc(cfg(feature = "std")))]
+pub
+enum OnUnwind {}
+
+#[cfg(feature = "std")]
+impl Strategy for OnUnwind {
+    #[inline]
+    fn should_run() -> bool {
+        ::std::thread::panicking()
+    }
+}
+
+/// Owns some `State`, accessible through [`Deref`][::core::ops::Deref] and
+/// [`DerefMut`][::core::ops::DerefMut], and feeds it to `on_drop` when dropped (if the
+/// [`Strategy`] says so), unless [`Self::defuse()`]-d.
+pub
+struct ScopeGuard<State, F : FnOnce(State), S : Strategy = Always>(
+    SafeManuallyDrop<ScopeGuardFields<State, F>, Self, S>,
+);
+
+/// The owned contents of a [`ScopeGuard`].
+pub
+struct ScopeGuardFields<State, F : FnOnce(State)> {
+    pub state: State,
+    pub on_drop: F,
+}
+
+impl<State, F : FnOnce(State)> ScopeGuardFields<State, F> {
+    /// Arms an [`Always`] [`ScopeGuard`].
+    #[inline]
+    pub
+    fn arm(self) -> ScopeGuard<State, F> {
+        ScopeGuard(SafeManuallyDrop::new(self))
+    }
+}
+
+impl<State, F : FnOnce(State), S : Strategy>
+    DropManually<ScopeGuardFields<State, F>, S>
+for
+    ScopeGuard<State, F, S>
+{
+    #[inline]
+    fn drop_manually(ScopeGuardFields { state, on_drop }: ScopeGuardFields<State, F>) {
+        if S::should_run() {
+            on_drop(state);
+        }
+    }
+}
+
+impl<State, F : FnOnce(State)> ScopeGuard<State, F> {
+    /// Creates an [`Always`] [`ScopeGuard`].
+    #[inline]
+    pub
+    const
+    fn new(state: State, on_drop: F) -> Self {
+        Self(SafeManuallyDrop::new(ScopeGuardFields { state, on_drop }))
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(feature = "better-docs", doc(cfg(feature = "std")))]
+impl<State, F : FnOnce(State)> ScopeGuard<State, F, OnSuccess> {
+    /// Creates an [`OnSuccess`] [`ScopeGuard`].
+    #[inline]
+    pub
+    const
+    fn on_success(state: State, on_drop: F) -> Self {
+        Self(SafeManuallyDrop::new(ScopeGuardFields { state, on_drop }))
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(feature = "better-docs", doc(cfg(feature = "std")))]
+impl<State, F : FnOnce(State)> ScopeGuard<State, F, OnUnwind> {
+    /// Creates an [`OnUnwind`] [`ScopeGuard`].
+    ///
+    /// ```rust
+    /// use ::safe_manually_drop::scope_guard::ScopeGuard;
+    ///
+    /// fn push_all(v: &mut Vec<i32>, items: impl IntoIterator<Item = i32>) {
+    ///     let len = v.len();
+    ///     // If `items` panics midway, restore `v` to its original state.
+    ///     let mut v = ScopeGuard::on_unwind(v, |v| v.truncate(len));
+    ///     for item in items {
+    ///         v.push(item);
+    ///     }
+    /// }
+    /// ```
+    #[inline]
+    pub
+    const
+    fn on_unwind(state: State, on_drop: F) -> Self {
+        Self(SafeManuallyDrop::new(ScopeGuardFields { state, on_drop }))
+    }
+}
+
+impl<State, F : FnOnce(State), S : Strategy> ScopeGuard<State, F, S> {
+    /// Disarms the guard, handing back its contents.
+    #[inline]
+    pub
+    fn defuse(self) -> ScopeGuardFields<State, F> {
+        self.0.into_inner_defusing_impl_Drop()
+    }
+}
+
+impl<State, F : FnOnce(State), S : Strategy> ::core::ops::Deref for ScopeGuard<State, F, S> {
+    type Target = State;
+
+    #[inline]
+    fn deref(&self) -> &State {
+        &self.0.state
+    }
+}
+
+impl<State, F : FnOnce(State), S : Strategy> ::core::ops::DerefMut for ScopeGuard<State, F, S> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut State {
+        &mut self.0.state
+    }
+}
@@ -0,0 +1,34 @@
+//! Transparent `serde` impls: a [`SafeManuallyDrop<FieldTy, …>`] (de)serializes exactly as its
+//! `FieldTy` would.
+
+use super::*;
+
+#[cfg_attr(feature = "better-docs", doc(cfg(feature = "serde")))]
+impl<FieldTy, ContainingType : DropManually<FieldTy, Tag>, Tag>
+    ::serde::Serialize
+for
+    SafeManuallyDrop<FieldTy, ContainingType, Tag>
+where
+    FieldTy : ::serde::Serialize,
+{
+    #[inline]
+    fn serialize<S : ::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        FieldTy::serialize(&self.field, serializer)
+    }
+}
+
+/// The deserialized `FieldTy` goes through [`SafeManuallyDrop::new()`], so the
+/// <code>impl [DropManually]\<FieldTy\></code> logic is armed, as usual.
+#[cfg_attr(feature = "better-docs", doc(cfg(feature = "serde")))]
+impl<'de, FieldTy, ContainingType : DropManually<FieldTy, Tag>, Tag>
+    ::serde::Deserialize<'de>
+for
+    SafeManuallyDrop<FieldTy, ContainingType, Tag>
+where
+    FieldTy : ::serde::Deserialize<'de>,
+{
+    #[inline]
+    fn deserialize<D : ::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        FieldTy::deserialize(deserializer).map(Self::new)
+    }
+}
@@ -0,0 +1,283 @@
+//! Rollback-on-drop transactions: [`Transactional`] and [`TxnGuard`].
+//!
+//! ```rust
+//! use ::safe_manually_drop::transaction::{Transactional, TxnGuard};
+//!
+//! # mod some_lib {
+//! #     pub struct RawTransaction(());
+//! #     impl RawTransaction {
+//! #         pub fn begin() -> Self { Self(()) }
+//! #         pub fn execute(&mut self, _: &str) {}
+//! #         pub fn commit(self) {}
+//! #         pub fn roll_back(self) {}
+//! #     }
+//! # }
+//! use some_lib::RawTransaction;
+//!
+//! impl Transactional for RawTransaction {
+//!     fn commit(self) {
+//!         RawTransaction::commit(self)
+//!     }
+//!
+//!     fn roll_back(self) {
+//!         RawTransaction::roll_back(self)
+//!     }
+//! }
+//!
+//! pub
+//! struct Transaction {
+//!     raw_txn: TxnGuard<RawTransaction>,
+//!     // Other fields still get dropped normally, including in `commit()`.
+//!     label: String,
+//! }
+//!
+//! impl Transaction {
+//!     pub
+//!     fn commit(self) {
+//!         self.raw_txn.commit();
+//!     }
+//! }
+//!
+//! let mut txn = Transaction {
+//!     raw_txn: TxnGuard::new(RawTransaction::begin()),
+//!     label: "example".into(),
+//! };
+//! txn.raw_txn.execute("…"); // `Deref{,Mut}`.
+//! txn.commit();
+//! ```
+//!
+//! ## Nested savepoints
+//!
+//! When the transaction supports [`Savepoints`], [`TxnGuard::savepoint()`] hands out a
+//! [`SavepointGuard`], which mutably borrows its parent (so that the borrow checker prevents using
+//! the parent while the child is around), and which:
+//!
+//!   - rolls back to its savepoint when dropped;
+//!
+//!   - folds its changes into the parent when [`.commit()`][SavepointGuard::commit]-ted.
+//!
+//! ```rust
+//! use ::safe_manually_drop::transaction::{
+//!     Savepoints, SavepointToken, Transactional, TxnGuard,
+//! };
+//!
+//! /// Toy transaction: a log of writes.
+//! struct Writes(Vec<&'static str>);
+//!
+//! impl Transactional for Writes {
+//!     fn commit(self) {}
+//!     fn roll_back(self) {}
+//! }
+//!
+//! impl Savepoints for Writes {
+//!     type Savepoint = usize;
+//!
+//!     fn savepoint(&mut self, _: SavepointToken) -> usize {
+//!         self.0.len()
+//!     }
+//!
+//!     fn roll_back_to(&mut self, len: usize, _: SavepointToken) {
+//!         self.0.truncate(len);
+//!     }
+//!
+//!     fn release(&mut self, _: usize, _: SavepointToken) {}
+//! }
+//!
+//! let mut txn = TxnGuard::new(Writes(vec![]));
+//! txn.0.push("a");
+//! {
+//!     let mut child = txn.savepoint();
+//!     child.0.push("b");
+//!     {
+//!         let mut grandchild = child.savepoint();
+//!         grandchild.0.push("c");
+//!         // dropped: rolled back.
+//!     }
+//!     child.commit();
+//! }
+//! assert_eq!(txn.0, ["a", "b"]);
+//! txn.commit();
+//! ```
+//!
+//! The [`Savepoints`] methods themselves require a [`SavepointToken`], which only this module can
+//! construct, so that they may only be called by the guards.
+//!
+//! That being said, the guards [`DerefMut`][::core::ops::DerefMut] to the transaction, so nothing
+//! prevents safe code from swapping it with another one (_e.g._, through
+//! [`::core::mem::replace()`]), in which case the guards would be releasing, or rolling back to,
+//! savepoints which do not belong to the transaction at hand: do not do that.
+
+use super::*;
+
+/// A transaction-like type, with owned-`self` operations to end it.
+pub
+trait Transactional {
+    /// Persists the changes.
+    fn commit(self);
+
+    /// Discards the changes. This is what a [`TxnGuard`] does when dropped.
+    fn roll_back(self);
+}
+
+/// Wraps a [`Transactional`], rolling it back on drop unless it is explicitly
+/// [`.commit()`][Self::commit]-ted.
+///
+/// It [`Deref`][::core::ops::Deref]s to the inner `T`.
+pub
+struct TxnGuard<T : Transactional>(
+    SafeManuallyDrop<T, Self>,
+);
+
+impl<T : Transactional> DropManually<T> for TxnGuard<T> {
+    #[inline]
+    fn drop_manually(txn: T) {
+        txn.roll_back();
+    }
+}
+
+impl<T : Transactional> TxnGuard<T> {
+    #[inline]
+    pub
+    const
+    fn new(txn: T) -> Self {
+        Self(SafeManuallyDrop::new(txn))
+    }
+
+    #[inline]
+    pub
+    fn commit(self) {
+        self.into_inner().commit();
+    }
+
+    /// Same as dropping the guard, but more explicit.
+    #[inline]
+    pub
+    fn roll_back(self) {
+        drop(self);
+    }
+
+    /// Disarms the guard, handing back the transaction, neither committed nor rolled back.
+    #[inline]
+    pub
+    fn into_inner(self) -> T {
+        self.0.into_inner_defusing_impl_Drop()
+    }
+}
+
+impl<T : Transactional> ::core::ops::Deref for TxnGuard<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T : Transactional> ::core::ops::DerefMut for TxnGuard<T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+/// A [`Transactional`] with support for nested savepoints: see [`SavepointGuard`].
+pub
+trait Savepoints : Transactional {
+    /// Whatever is needed to later roll back to, or release, a savepoint.
+    type Savepoint;
+
+    /// Marks the current state of the transaction.
+    fn savepoint(&mut self, token: SavepointToken) -> Self::Savepoint;
+
+    /// Discards the changes made since the `savepoint`.
+    fn roll_back_to(&mut self, savepoint: Self::Savepoint, token: SavepointToken);
+
+    /// Keeps the changes made since the `savepoint`, folding them into the enclosing
+    /// savepoint (or transaction).
+    fn release(&mut self, savepoint: Self::Savepoint, token: SavepointToken);
+}
+
+/// Proof that a [`Savepoints`] method is being called by a [`SavepointGuard`].
+///
+/// It cannot be constructed outside of this module, so that the savepoint methods cannot be
+/// called directly, lest the nesting order be broken.
+#[derive(Debug)]
+pub
+struct SavepointToken(());
+
+impl<T : Savepoints> TxnGuard<T> {
+    /// Starts a nested [`SavepointGuard`].
+    #[inline]
+    pub
+    fn savepoint(&mut self) -> SavepointGuard<'_, T> {
+        SavepointGuard::new(&mut self.0)
+    }
+}
+
+/// A savepoint within a [`Savepoints`] transaction, obtained from [`TxnGuard::savepoint()`] (or,
+/// for further nesting, from [`SavepointGuard::savepoint()`]).
+///
+/// It rolls back to its savepoint on drop, unless explicitly [`.commit()`][Self::commit]-ted.
+///
+/// It [`Deref`][::core::ops::Deref]s to the transaction.
+pub
+struct SavepointGuard<'parent, T : Savepoints>(
+    SafeManuallyDrop<(&'parent mut T, T::Savepoint), Self>,
+);
+
+impl<'parent, T : Savepoints>
+    DropManually<(&'parent mut T, T::Savepoint)>
+for
+    SavepointGuard<'parent, T>
+{
+    #[inline]
+    fn drop_manually((txn, savepoint): (&'parent mut T, T::Savepoint)) {
+        txn.roll_back_to(savepoint, SavepointToken(()));
+    }
+}
+
+impl<'parent, T : Savepoints> SavepointGuard<'parent, T> {
+    #[inline]
+    fn new(txn: &'parent mut T) -> Self {
+        let savepoint = txn.savepoint(SavepointToken(()));
+        Self(SafeManuallyDrop::new((txn, savepoint)))
+    }
+
+    /// Starts a further nested [`SavepointGuard`].
+    #[inline]
+    pub
+    fn savepoint(&mut self) -> SavepointGuard<'_, T> {
+        SavepointGuard::new(self.0.0)
+    }
+
+    /// Folds the changes made since this savepoint into the parent.
+    #[inline]
+    pub
+    fn commit(self) {
+        let (txn, savepoint) = self.0.into_inner_defusing_impl_Drop();
+        txn.release(savepoint, SavepointToken(()));
+    }
+
+    /// Same as dropping the guard, but more explicit.
+    #[inline]
+    pub
+    fn roll_back(self) {
+        drop(self);
+    }
+}
+
+impl<T : Savepoints> ::core::ops::Deref for SavepointGuard<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        self.0.0
+    }
+}
+
+impl<T : Savepoints> ::core::ops::DerefMut for SavepointGuard<'_, T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        self.0.0
+    }
+}
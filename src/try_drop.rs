@@ -0,0 +1,255 @@
+//! Fallible drop hooks: [`TryDropManually`], whose errors get handed to an _error sink_.
+//!
+//! ```rust
+//! use ::safe_manually_drop::prelude::*;
+//!
+//! # mod some_lib {
+//! #     pub struct File(());
+//! #     impl File { pub fn close(self) -> Result<(), String> { Ok(()) } }
+//! # }
+//! use some_lib::File;
+//!
+//! pub
+//! struct Logs {
+//!     file: SafeManuallyDrop<File, Fallible<Self>>,
+//! }
+//!
+//! impl TryDropManually<File> for Logs {
+//!     type Error = String;
+//!
+//!     fn try_drop_manually(file: File) -> Result<(), String> {
+//!         file.close()
+//!     }
+//! }
+//! ```
+//!
+//! ## Error sinks
+//!
+//! Errors returned by the hooks are reported, as a [`DropError`], to the first of:
+//!
+#![cfg_attr(feature = "std", doc = "\
+  1. the innermost handler installed by [`with_scoped_sink()`] on the current thread (`std` \
+     only);\n\
+")]
+//!  1. the handler installed by [`set_global_sink()`];
+//!
+//!  1. the default one, which prints the error to stderr when the `std` feature is enabled, and
+//!     which otherwise does nothing (so `no_std` users ought to call [`set_global_sink()`]).
+
+use super::*;
+
+/// Same as [`DropManually`], but for a hook which may fail.
+///
+/// In order to use it, rather than `SafeManuallyDrop<FieldTy, Self>`, the field ought to be a
+/// <code>[SafeManuallyDrop]\<FieldTy, [Fallible]\<Self\>\></code>.
+///
+/// See [the module docs][self] for where the errors end up.
+#[diagnostic::on_unimplemented(
+    note = "\
+In order for a `SafeManuallyDrop<FieldTy, Fallible<ContainingType>>` to be used, you have to \
+provide an `impl<…> TryDropManually<FieldTy> for ContainingType<…> {{`.\
+    ",
+)]
+pub
+trait TryDropManually<FieldTy, Tag = ()> {
+    /// The error returned by the hook, to be reported through some [`DropError`].
+    type Error : ::core::fmt::Debug + 'static;
+
+    /// Forwarded to [`DropManually::ON_PANIC`].
+    const ON_PANIC: OnPanic = OnPanic::Propagate;
+
+    fn try_drop_manually(_: FieldTy) -> Result<(), Self::Error>;
+}
+
+/// The `ContainingType` adapter for a [`TryDropManually`] impl to be used as the drop logic of a
+/// [`SafeManuallyDrop`].
+///
+/// ```rust
+/// # use ::safe_manually_drop::prelude::*;
+/// # struct Example {
+/// field: SafeManuallyDrop<String, Fallible<Self>>,
+/// # }
+/// # impl TryDropManually<String> for Example {
+/// #     type Error = ();
+/// #     fn try_drop_manually(_: String) -> Result<(), ()> { Ok(()) }
+/// # }
+/// ```
+pub
+struct Fallible<ContainingType>(
+    ::core::convert::Infallible,
+    PD<fn() -> ContainingType>,
+);
+
+impl<FieldTy, ContainingType : TryDropManually<FieldTy, Tag>, Tag>
+    DropManually<FieldTy, Tag>
+for
+    Fallible<ContainingType>
+{
+    const ON_PANIC: OnPanic = ContainingType::ON_PANIC;
+
+    #[inline]
+    fn drop_manually(field: FieldTy) {
+        if let Err(ref error) = ContainingType::try_drop_manually(field) {
+            report(&DropError {
+                error,
+                error_as_any: error,
+                field_type_name: ::core::any::type_name::<FieldTy>(),
+                containing_type_name: ::core::any::type_name::<ContainingType>(),
+            });
+        }
+    }
+}
+
+/// An error returned by some [`TryDropManually::try_drop_manually()`] hook, as seen by the
+/// error sinks.
+///
+/// Its [`Display`][::core::fmt::Display] impl mentions the types involved, as well as the
+/// [`Debug`][::core::fmt::Debug] output of the error.
+#[derive(Debug, Clone, Copy)]
+pub
+struct DropError<'r> {
+    error: &'r dyn ::core::fmt::Debug,
+    error_as_any: &'r dyn ::core::any::Any,
+    /// The [`::core::any::type_name()`] of the `FieldTy`.
+    pub field_type_name: &'static str,
+    /// The [`::core::any::type_name()`] of the `ContainingType`.
+    pub containing_type_name: &'static str,
+}
+
+impl<'r> DropError<'r> {
+    /// The type-erased [`TryDropManually::Error`].
+    #[inline]
+    pub
+    fn error(&self) -> &'r dyn ::core::fmt::Debug {
+        self.error
+    }
+
+    /// Tries to recover the concrete [`TryDropManually::Error`].
+    #[inline]
+    pub
+    fn downcast_ref<E : 'static>(&self) -> Option<&'r E> {
+        self.error_as_any.downcast_ref()
+    }
+}
+
+impl ::core::fmt::Display for DropError<'_> {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        write!(
+            f,
+            "`<{} as TryDropManually<{}>>::try_drop_manually()` failed: {:?}",
+            self.containing_type_name,
+            self.field_type_name,
+            self.error,
+        )
+    }
+}
+
+#[cfg(target_has_atomic = "ptr")]
+static GLOBAL_SINK: ::core::sync::atomic::AtomicPtr<()> = {
+    ::core::sync::atomic::AtomicPtr::new(::core::ptr::null_mut())
+};
+
+/// Installs the process-wide error sink, used when no scoped one is (see
+/// [the module docs][self]).
+///
+/// Calling it again replaces the previously installed sink.
+#[cfg(target_has_atomic = "ptr")]
+pub
+fn set_global_sink(sink: fn(&DropError<'_>)) {
+    GLOBAL_SINK.store(sink as *mut (), ::core::sync::atomic::Ordering::Release);
+}
+
+#[cfg(feature = "std")]
+type ScopedSink = ::core::ptr::NonNull<dyn FnMut(&DropError<'_>)>;
+
+#[cfg(feature = "std")]
+::std::thread_local! {
+    static SCOPED_SINK: ::core::cell::Cell<Option<ScopedSink>> = const {
+        ::core::cell::Cell::new(None)
+    };
+}
+
+/// Restores the previous scoped sink, even on unwind.
+#[cfg(feature = "std")]
+struct RestoreScopedSink(Option<ScopedSink>);
+
+#[cfg(feature = "std")]
+impl Drop for RestoreScopedSink {
+    fn drop(&mut self) {
+        let _ = SCOPED_SINK.try_with(|it| it.set(self.0));
+    }
+}
+
+/// Runs `scope`, with the errors of the [`TryDropManually`] hooks run during it, on the current
+/// thread, going to `sink`.
+///
+/// These can be nested, in which case the innermost `sink` takes precedence. Should `sink`
+/// itself drop a value whose hook fails, that error skips the scoped sinks altogether (it goes to
+/// the [global][set_global_sink] or default one).
+///
+/// ```rust
+/// use ::safe_manually_drop::{prelude::*, try_drop::with_scoped_sink};
+///
+/// struct Flushed(SafeManuallyDrop<(), Fallible<Self>>);
+///
+/// impl TryDropManually<()> for Flushed {
+///     type Error = &'static str;
+///
+///     fn try_drop_manually((): ()) -> Result<(), &'static str> {
+///         Err("disk full")
+///     }
+/// }
+///
+/// let mut errors = vec![];
+/// with_scoped_sink(&mut |err| errors.push(*err.downcast_ref::<&str>().unwrap()), || {
+///     drop(Flushed(().into()));
+/// });
+/// assert_eq!(errors, ["disk full"]);
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(feature = "better-docs", doc(cfg(feature = "std")))]
+pub
+fn with_scoped_sink<R>(
+    sink: &mut dyn FnMut(&DropError<'_>),
+    scope: impl FnOnce() -> R,
+) -> R
+{
+    let sink: ScopedSink = unsafe {
+        // Safety: lifetime erasure; the pointer is only reachable from within `scope`, since
+        // `RestoreScopedSink` uninstalls it before `sink`'s borrow ends (even on unwind).
+        ::core::mem::transmute::<::core::ptr::NonNull<dyn FnMut(&DropError<'_>) + '_>, _>(
+            sink.into(),
+        )
+    };
+    let _restore = RestoreScopedSink(SCOPED_SINK.with(|it| it.replace(Some(sink))));
+    scope()
+}
+
+fn report(error: &DropError<'_>) {
+    #[cfg(feature = "std")]
+    if let Some(mut sink) = SCOPED_SINK.try_with(|it| it.take()).ok().flatten() {
+        // Uninstalled while it runs, so that a reentrant `report()` cannot alias it.
+        let _restore = RestoreScopedSink(Some(sink));
+        return unsafe {
+            // Safety: see `with_scoped_sink()`.
+            sink.as_mut()(error)
+        };
+    }
+
+    #[cfg(target_has_atomic = "ptr")] {
+        let sink = GLOBAL_SINK.load(::core::sync::atomic::Ordering::Acquire);
+        if !sink.is_null() {
+            return unsafe {
+                // Safety: only ever set from a `fn(&DropError<'_>)` by `set_global_sink()`.
+                ::core::mem::transmute::<*mut (), fn(&DropError<'_>)>(sink)(error)
+            };
+        }
+    }
+
+    #[cfg(feature = "std")] {
+        ::std::eprintln!("{error}");
+    }
+    #[cfg(not(feature = "std"))] {
+        let _ = error;
+    }
+}
@@ -0,0 +1,155 @@
+//! Two-phase commit across several [`TxnGuard`]s: [`Coordinator`].
+//!
+//! ```rust
+//! use ::safe_manually_drop::{
+//!     transaction::{Transactional, TxnGuard},
+//!     two_phase::{Coordinator, Prepare},
+//! };
+//!
+//! # mod some_lib {
+//! #     pub struct FileTxn; pub struct MetadataTxn;
+//! #     impl FileTxn {
+//! #         pub fn fsync(&mut self) -> Result<(), String> { Ok(()) }
+//! #         pub fn commit(self) {} pub fn roll_back(self) {}
+//! #     }
+//! #     impl MetadataTxn {
+//! #         pub fn check(&mut self) -> Result<(), String> { Ok(()) }
+//! #         pub fn commit(self) {} pub fn roll_back(self) {}
+//! #     }
+//! # }
+//! use some_lib::{FileTxn, MetadataTxn};
+//!
+//! impl Transactional for FileTxn {
+//!     fn commit(self) { FileTxn::commit(self) }
+//!     fn roll_back(self) { FileTxn::roll_back(self) }
+//! }
+//!
+//! impl Prepare for FileTxn {
+//!     type Error = String;
+//!     fn prepare(&mut self) -> Result<(), String> { self.fsync() }
+//! }
+//!
+//! impl Transactional for MetadataTxn {
+//!     fn commit(self) { MetadataTxn::commit(self) }
+//!     fn roll_back(self) { MetadataTxn::roll_back(self) }
+//! }
+//!
+//! impl Prepare for MetadataTxn {
+//!     type Error = String;
+//!     fn prepare(&mut self) -> Result<(), String> { self.check() }
+//! }
+//!
+//! let coordinator = Coordinator::new((
+//!     TxnGuard::new(FileTxn),
+//!     TxnGuard::new(MetadataTxn),
+//! ));
+//! // Either both get committed, or both get rolled back.
+//! coordinator.commit::<String>()?;
+//! # Ok::<(), String>(())
+//! ```
+
+use super::*;
+use transaction::{Transactional, TxnGuard};
+
+/// A [`Transactional`] which can take part in a two-phase commit.
+pub
+trait Prepare : Transactional {
+    type Error;
+
+    /// First phase: checks that [`Transactional::commit()`] is to succeed.
+    ///
+    /// Once every participant of a [`Coordinator`] has successfully prepared, they all get
+    /// committed; otherwise, they all get rolled back.
+    fn prepare(&mut self) -> Result<(), Self::Error>;
+}
+
+/// The participants of a [`Coordinator`]: tuples of [`TxnGuard`]s, whose [`Prepare::Error`]s
+/// convert [`Into`] a common `E`.
+pub
+trait Participants<E> {
+    fn prepare_all(&mut self) -> Result<(), E>;
+
+    fn commit_all(self);
+}
+
+macro_rules! impl_Participants_for_tuples {(
+    $( ($($T:ident),+ $(,)?) )*
+) => ($(
+    impl<E, $($T : Prepare),+>
+        Participants<E>
+    for
+        ($(TxnGuard<$T>,)+)
+    where
+        $( $T::Error : Into<E>, )+
+    {
+        #[inline]
+        fn prepare_all(&mut self) -> Result<(), E> {
+            #[allow(nonstandard_style)]
+            let ($($T,)+) = self;
+            $(
+                Prepare::prepare(&mut **$T).map_err(Into::into)?;
+            )+
+            Ok(())
+        }
+
+        #[inline]
+        fn commit_all(self) {
+            #[allow(nonstandard_style)]
+            let ($($T,)+) = self;
+            $(
+                $T.commit();
+            )+
+        }
+    }
+)*)}
+
+impl_Participants_for_tuples! {
+    (A,)
+    (A, B)
+    (A, B, C)
+    (A, B, C, D)
+    (A, B, C, D, F)
+    (A, B, C, D, F, G)
+    (A, B, C, D, F, G, H)
+    (A, B, C, D, F, G, H, I)
+}
+
+/// Commits several [`TxnGuard`]s together: see [the module docs][self].
+///
+/// Dropping it without [`.commit()`][Self::commit]-ting rolls back every participant.
+pub
+struct Coordinator<Ps> {
+    participants: Ps,
+}
+
+impl<Ps> Coordinator<Ps> {
+    #[inline]
+    pub
+    const
+    fn new(participants: Ps) -> Self {
+        Self { participants }
+    }
+
+    /// Access to the participants, before the commit.
+    #[inline]
+    pub
+    fn participants_mut(&mut self) -> &mut Ps {
+        &mut self.participants
+    }
+
+    /// Prepares every participant, in order, and then, if they all succeeded, commits them all.
+    ///
+    /// Otherwise, every participant is rolled back, and the first error is returned.
+    ///
+    /// Should a [`Transactional::commit()`] panic, the participants which had not been
+    /// committed yet get rolled back.
+    pub
+    fn commit<E>(mut self) -> Result<(), E>
+    where
+        Ps : Participants<E>,
+    {
+        self.participants.prepare_all()?;
+        self.participants.commit_all();
+        Ok(())
+    }
+}
@@ -0,0 +1,73 @@
+use ::core::cell::Cell;
+use ::safe_manually_drop::{
+    prelude::*,
+    try_drop::with_scoped_sink,
+};
+
+::std::thread_local! {
+    static CLOSED: Cell<usize> = const { Cell::new(0) };
+}
+
+struct RawFile {
+    fail: bool,
+}
+
+impl RawFile {
+    fn close(self) -> Result<(), &'static str> {
+        CLOSED.with(|it| it.set(it.get() + 1));
+        if self.fail { Err("EIO") } else { Ok(()) }
+    }
+}
+
+struct File {
+    raw: SafeManuallyDrop<RawFile, Closeable<Self>>,
+}
+
+impl CloseManually<RawFile> for File {
+    type Output = Result<(), &'static str>;
+
+    fn close_manually(raw: RawFile) -> Self::Output {
+        raw.close()
+    }
+}
+
+impl File {
+    fn open(fail: bool) -> Self {
+        Self { raw: RawFile { fail }.into() }
+    }
+
+    fn close(self) -> Result<(), &'static str> {
+        self.raw.close()
+    }
+}
+
+#[test]
+fn explicit_close_runs_the_hook_once() {
+    let closed = CLOSED.with(Cell::get);
+    assert_eq!(File::open(false).close(), Ok(()));
+    assert_eq!(File::open(true).close(), Err("EIO"));
+    assert_eq!(CLOSED.with(Cell::get), closed + 2);
+    // Implicit drop: best-effort fallback.
+    drop(File::open(true));
+    assert_eq!(CLOSED.with(Cell::get), closed + 3);
+}
+
+struct Fallibly {
+    raw: SafeManuallyDrop<RawFile, Fallible<Self>>,
+}
+
+impl TryDropManually<RawFile> for Fallibly {
+    type Error = &'static str;
+
+    fn try_drop_manually(raw: RawFile) -> Result<(), &'static str> {
+        raw.close()
+    }
+}
+
+#[test]
+fn closing_a_fallible_hook_returns_the_error() {
+    with_scoped_sink(&mut |_| unreachable!(), || {
+        let it = Fallibly { raw: RawFile { fail: true }.into() };
+        assert_eq!(it.raw.close(), Err("EIO"));
+    });
+}
@@ -0,0 +1,91 @@
+use ::safe_manually_drop::prelude::*;
+
+mod common;
+use common::{log, take_log};
+
+struct Connection(SafeManuallyDrop<String, Self>);
+
+impl DropManually<String> for Connection {
+    fn drop_manually(addr: String) {
+        log(format!("close {addr}"));
+    }
+}
+
+struct AuthenticatedConnection {
+    _session: SafeManuallyDrop<(String, String), Self>,
+}
+
+impl DropManually<(String, String)> for AuthenticatedConnection {
+    fn drop_manually((addr, user): (String, String)) {
+        log(format!("log {user} out of {addr}"));
+    }
+}
+
+impl Connection {
+    fn authenticate(self, user: &str) -> AuthenticatedConnection {
+        AuthenticatedConnection {
+            _session: SafeManuallyDrop::map(self.0, |addr| (addr, user.into())),
+        }
+    }
+}
+
+#[test]
+fn map() {
+    let conn = Connection(SafeManuallyDrop::new("host".into()));
+    let conn = conn.authenticate("me");
+    assert!(take_log().is_empty());
+    drop(conn);
+    assert_eq!(take_log(), ["log me out of host"]);
+}
+
+#[test]
+fn replace() {
+    let mut conn = Connection(SafeManuallyDrop::new("a".into()));
+    assert_eq!(SafeManuallyDrop::replace(&mut conn.0, "b".into()), "a");
+    assert!(take_log().is_empty());
+    drop(conn);
+    assert_eq!(take_log(), ["close b"]);
+}
+
+#[test]
+fn take_and_rearm() {
+    let mut conn = Connection(SafeManuallyDrop::new("a".into()));
+    SafeManuallyDrop::take_and_rearm(&mut conn.0, |addr| addr + "b");
+    assert_eq!(*conn.0, "ab");
+    assert!(take_log().is_empty());
+    drop(conn);
+    assert_eq!(take_log(), ["close ab"]);
+}
+
+#[test]
+fn panicking_map_aborts() {
+    common::assert_aborts(
+        "panicking_map_aborts",
+        &["about to abort", "::map()` panicked"],
+        || {
+            let conn = Connection(SafeManuallyDrop::new("host".into()));
+            let _: SafeManuallyDrop<(), ::safe_manually_drop::policy::Leak> =
+                SafeManuallyDrop::map(conn.0, |_| panic!("about to abort"));
+        },
+    );
+}
+
+struct Counter(SafeManuallyDrop<::core::cell::Cell<i32>, Self>);
+
+impl DropManually<::core::cell::Cell<i32>> for Counter {
+    fn drop_manually(count: ::core::cell::Cell<i32>) {
+        log(format!("count: {}", count.get()));
+    }
+}
+
+#[test]
+fn field_methods_are_not_shadowed() {
+    let counter = Counter(SafeManuallyDrop::new(0.into()));
+    // `Cell::replace()`, through `Deref`.
+    assert_eq!(counter.0.replace(5), 0);
+    drop(counter);
+    assert_eq!(take_log(), ["count: 5"]);
+
+    let opt = SafeManuallyDrop::<_, ::safe_manually_drop::policy::DropNormally>::new(Some(1));
+    assert_eq!(opt.map(|n| n + 1), Some(2));
+}
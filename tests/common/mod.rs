@@ -0,0 +1,56 @@
+//! Helpers shared by the integration tests.
+#![allow(dead_code)] // Each test file only uses some of them.
+
+use ::core::{cell::RefCell, ops::Not as _};
+
+::std::thread_local! {
+    static LOG: RefCell<Vec<String>> = const { RefCell::new(vec![]) };
+}
+
+/// Appends a line to the (thread-local) log, _e.g._, from some drop hook.
+pub
+fn log(line: String) {
+    LOG.with(|it| it.borrow_mut().push(line));
+}
+
+/// Empties the log, returning what it contained.
+pub
+fn take_log() -> Vec<String> {
+    LOG.with(RefCell::take)
+}
+
+const ABORT_SUBPROCESS: &str = "SAFE_MANUALLY_DROP_ABORT_SUBPROCESS";
+
+/// The id of the test run: the process id, shared with the [`assert_aborts()`] subprocesses, _e.g._,
+/// to name some temporary directory they both use.
+pub
+fn run_id() -> u32 {
+    match ::std::env::var(ABORT_SUBPROCESS) {
+        | Ok(parent_id) => parent_id.parse().unwrap(),
+        | Err(_) => ::std::process::id(),
+    }
+}
+
+/// Asserts that `body` aborts the process, with a stderr containing each of `expected_stderr`.
+///
+/// Since an abort cannot be caught, the current test binary is re-executed, running only the
+/// `test_name` test (the one calling this), wherein `body` then gets to run.
+pub
+fn assert_aborts(test_name: &str, expected_stderr: &[&str], body: impl FnOnce()) {
+    if ::std::env::var_os(ABORT_SUBPROCESS).is_some() {
+        body();
+        unreachable!("`{test_name}` did not abort");
+    }
+    let output =
+        ::std::process::Command::new(::std::env::current_exe().unwrap())
+            .args(["--exact", test_name, "--nocapture"])
+            .env(ABORT_SUBPROCESS, ::std::process::id().to_string())
+            .output()
+            .unwrap()
+    ;
+    assert!(output.status.success().not());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    for expected in expected_stderr {
+        assert!(stderr.contains(expected), "{stderr}");
+    }
+}
@@ -0,0 +1,49 @@
+use ::std::{cell::RefCell, rc::Rc};
+use ::safe_manually_drop::prelude::*;
+
+type Sink = Rc<RefCell<Vec<String>>>;
+
+struct Job {
+    name: SafeManuallyDropWithContext<String, Self>,
+}
+
+impl DropManuallyWithContext<String> for Job {
+    type Context = (Sink, &'static str);
+
+    fn drop_manually_with_context(name: String, (sink, prefix): (Sink, &'static str)) {
+        sink.borrow_mut().push(format!("{prefix}{name}"));
+    }
+}
+
+impl Job {
+    fn new(name: &str, sink: &Sink, prefix: &'static str) -> Self {
+        Self {
+            name: SafeManuallyDropWithContext::new(name.into(), (sink.clone(), prefix)),
+        }
+    }
+}
+
+#[test]
+fn runtime_destinations() {
+    let a = Sink::default();
+    let b = Sink::default();
+    let mut job = Job::new("x", &a, "a:");
+    job.name.push('!');
+    assert_eq!(job.name.context().1, "a:");
+    drop(job);
+    drop(Job::new("y", &b, "b:"));
+    let mut job = Job::new("z", &a, "a:");
+    job.name.context_mut().0 = b.clone();
+    drop(job);
+    assert_eq!(*a.borrow(), ["a:x!"]);
+    assert_eq!(*b.borrow(), ["b:y", "a:z"]);
+}
+
+#[test]
+fn defusing() {
+    let sink = Sink::default();
+    let (name, (ctx, prefix)) = Job::new("x", &sink, "").name.into_inner_defusing_impl_Drop();
+    assert_eq!((&name[..], prefix), ("x", ""));
+    assert!(Rc::ptr_eq(&ctx, &sink));
+    assert!(sink.borrow().is_empty());
+}
@@ -0,0 +1,136 @@
+use ::core::cell::RefCell;
+use ::safe_manually_drop::{defer, defer::Defer};
+
+#[test]
+fn lifo() {
+    let log = RefCell::new(vec![]);
+    {
+        defer! { log.borrow_mut().push(1) }
+        let _two = Defer::new(|| log.borrow_mut().push(2));
+        defer! {
+            log.borrow_mut().push(3);
+        }
+        log.borrow_mut().push(0);
+    }
+    assert_eq!(log.into_inner(), [0, 3, 2, 1]);
+}
+
+#[test]
+fn cancel() {
+    let log = RefCell::new(vec![]);
+    {
+        let _one = Defer::new(|| log.borrow_mut().push(1));
+        let two = Defer::new(|| log.borrow_mut().push(2));
+        let two = two.cancel();
+        two();
+    }
+    assert_eq!(log.into_inner(), [2, 1]);
+}
+
+#[test]
+fn runs_on_unwind() {
+    let log = RefCell::new(vec![]);
+    let caught = ::std::panic::catch_unwind(::core::panic::AssertUnwindSafe(|| {
+        defer! { log.borrow_mut().push(1) }
+        defer! { log.borrow_mut().push(2) }
+        panic!("boom");
+    }));
+    assert!(caught.is_err());
+    assert_eq!(log.into_inner(), [2, 1]);
+}
+
+#[test]
+fn panicking_deferred_closure_propagates() {
+    let log = RefCell::new(vec![]);
+    let caught = ::std::panic::catch_unwind(::core::panic::AssertUnwindSafe(|| {
+        defer! { log.borrow_mut().push(1) }
+        defer! { panic!("in defer") }
+        log.borrow_mut().push(0);
+    }));
+    assert_eq!(caught.unwrap_err().downcast_ref::<&str>(), Some(&"in defer"));
+    // The outer deferred closure still ran, during the unwinding.
+    assert_eq!(log.into_inner(), [0, 1]);
+}
+
+mod stack {
+    use super::*;
+    use ::safe_manually_drop::defer::DeferStack;
+
+    #[test]
+    fn lifo() {
+        let log = RefCell::new(vec![]);
+        {
+            let mut stack = DeferStack::new();
+            for i in 0..3 {
+                stack.push(i.to_string(), |it| log.borrow_mut().push(it));
+            }
+            stack.push_fn(|| log.borrow_mut().push("fn".into()));
+        }
+        assert_eq!(log.into_inner(), ["fn", "2", "1", "0"]);
+    }
+
+    #[test]
+    fn disarm_and_remove() {
+        let log = RefCell::new(vec![]);
+        {
+            let mut stack = DeferStack::new();
+            stack.push(0, |it| log.borrow_mut().push(it));
+            let one = stack.push(1, |it| log.borrow_mut().push(it));
+            let two = stack.push(2, |it| log.borrow_mut().push(it));
+            stack.push(3, |it| log.borrow_mut().push(it));
+            stack.disarm(one);
+            let two = stack.remove(two);
+            two();
+            // Reuses the indices of trailing disarmed entries.
+            let four = stack.push(4, |it| log.borrow_mut().push(it));
+            stack.disarm(four);
+            stack.push(5, |it| log.borrow_mut().push(it));
+        }
+        assert_eq!(log.into_inner(), [2, 5, 3, 0]);
+    }
+
+    #[test]
+    #[should_panic = "issued by another `DeferStack`"]
+    fn foreign_handle() {
+        let mut stack = DeferStack::new();
+        let handle = DeferStack::new().push_fn(|| ());
+        stack.disarm(handle);
+    }
+
+    #[test]
+    fn foreign_handle_with_a_valid_index() {
+        let ran = RefCell::new(false);
+        let caught = ::std::panic::catch_unwind(::core::panic::AssertUnwindSafe(|| {
+            let mut a = DeferStack::new();
+            let mut b = DeferStack::new();
+            b.push_fn(|| *ran.borrow_mut() = true);
+            let handle = a.push_fn(|| ());
+            b.disarm(handle);
+        }));
+        assert!(caught.is_err());
+        // `b`'s entry did not get disarmed.
+        assert!(ran.into_inner());
+    }
+
+    #[test]
+    fn release() {
+        let log = RefCell::new(vec![]);
+        let mut stack = DeferStack::new();
+        stack.push_fn(|| log.borrow_mut().push(0));
+        stack.release();
+        assert!(log.into_inner().is_empty());
+    }
+
+    #[test]
+    fn panicking_action_does_not_skip_the_others() {
+        let log = RefCell::new(vec![]);
+        let caught = ::std::panic::catch_unwind(::core::panic::AssertUnwindSafe(|| {
+            let mut stack = DeferStack::new();
+            stack.push_fn(|| log.borrow_mut().push(0));
+            stack.push_fn(|| panic!());
+            stack.push_fn(|| log.borrow_mut().push(2));
+        }));
+        assert!(caught.is_err());
+        assert_eq!(log.into_inner(), [2, 0]);
+    }
+}
@@ -0,0 +1,77 @@
+use ::core::cell::Cell;
+use ::safe_manually_drop::prelude::*;
+
+::std::thread_local! {
+    static DROP_COUNT: Cell<usize> = const { Cell::new(0) };
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+struct Counted {
+    id: SafeManuallyDrop<u8, Self>,
+    name: &'static str,
+}
+
+impl DropManually<u8> for Counted {
+    fn drop_manually(_: u8) {
+        DROP_COUNT.with(|it| it.set(it.get() + 1));
+    }
+}
+
+fn drop_count() -> usize {
+    DROP_COUNT.with(Cell::get)
+}
+
+#[test]
+fn derives_forward_to_the_field() {
+    use ::std::{
+        borrow::Borrow,
+        collections::hash_map::DefaultHasher,
+        hash::{Hash, Hasher},
+    };
+
+    let a = Counted { id: 1.into(), name: "a" };
+    let b = Counted { id: 2.into(), name: "b" };
+    assert_eq!(format!("{a:?}"), r#"Counted { id: 1, name: "a" }"#);
+    assert!(a < b);
+    assert_ne!(a, b);
+    assert_eq!(Counted::default(), Counted { id: 0.into(), name: "" });
+
+    fn hash_of(it: &impl Hash) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        it.hash(&mut hasher);
+        hasher.finish()
+    }
+    assert_eq!(hash_of(&a.id), hash_of(&1_u8));
+
+    let id: &u8 = a.id.as_ref();
+    assert_eq!(*id, 1);
+    let id: &u8 = a.id.borrow();
+    assert_eq!(*id, 1);
+}
+
+#[test]
+fn clones_run_their_own_hook() {
+    let before = drop_count();
+    let original = Counted { id: 42.into(), name: "original" };
+    let clone = original.clone();
+    assert_eq!(*clone.id, 42);
+    drop(original);
+    assert_eq!(drop_count(), before + 1);
+    drop(clone);
+    assert_eq!(drop_count(), before + 2);
+}
+
+#[test]
+fn clone_from_runs_the_hook_of_the_overwritten_value() {
+    let before = drop_count();
+    let mut a = Counted { id: 1.into(), name: "a" };
+    let b = Counted { id: 2.into(), name: "b" };
+    a.id.clone_from(&b.id);
+    assert_eq!(*a.id, 2);
+    assert_eq!(drop_count(), before + 1);
+
+    let mut v = vec![a.id.clone()];
+    let source = vec![b.id.clone()];
+    v.clone_from(&source);
+    assert_eq!(drop_count(), before + 2);
+}
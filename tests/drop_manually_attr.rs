@@ -0,0 +1,103 @@
+use ::core::cell::Cell;
+use ::safe_manually_drop::prelude::*;
+
+#[drop_manually]
+pub
+struct Defer<F>(
+    #[drop_manually(call)]
+    F,
+)
+where
+    F : FnOnce(),
+;
+
+fn call(f: impl FnOnce()) {
+    f();
+}
+
+#[drop_manually]
+struct Logged<'name, T> {
+    #[drop_manually(Self::log_len)]
+    values: Vec<T>,
+    #[drop_manually(Self::log_name)]
+    name: &'name str,
+}
+
+impl<T> Logged<'_, T> {
+    fn log_len(values: Vec<T>) {
+        LOG.with(|it| it.borrow_mut().push(format!("len: {}", values.len())));
+    }
+
+    fn log_name(name: &str) {
+        LOG.with(|it| it.borrow_mut().push(format!("name: {name}")));
+    }
+}
+
+::std::thread_local! {
+    static LOG: ::core::cell::RefCell<Vec<String>> = const { ::core::cell::RefCell::new(vec![]) };
+}
+
+#[test]
+fn tuple_struct_with_where_clause() {
+    let counter = &Cell::new(0);
+    let defer = Defer(SafeManuallyDrop::new(|| counter.set(counter.get() + 1)));
+    assert_eq!(counter.get(), 0);
+    drop(defer);
+    assert_eq!(counter.get(), 1);
+
+    let defer = Defer(SafeManuallyDrop::new(|| counter.set(counter.get() + 1)));
+    let _f = defer.0.into_inner_defusing_impl_Drop();
+    assert_eq!(counter.get(), 1);
+}
+
+#[test]
+fn several_fields_and_lifetimes() {
+    let name = String::from("it");
+    let it = Logged {
+        values: vec![(), ()].into(),
+        name: name[..].into(),
+    };
+    assert_eq!(it.values.len(), 2);
+    drop(it);
+    // Fields are dropped in declaration order.
+    LOG.with(|it| assert_eq!(*it.borrow(), ["len: 2", "name: it"]));
+}
+
+#[drop_manually]
+struct Named {
+    #[drop_manually(Self::log_first)]
+    first: String,
+    #[drop_manually(Self::log_last)]
+    r#last: String,
+}
+
+impl Named {
+    fn log_first(first: String) {
+        LOG.with(|it| it.borrow_mut().push(format!("first: {first}")));
+    }
+
+    fn log_last(last: String) {
+        LOG.with(|it| it.borrow_mut().push(format!("last: {last}")));
+    }
+}
+
+#[drop_manually]
+struct Pair<A, B>(
+    #[drop_manually(drop)]
+    A,
+    #[drop_manually(drop)]
+    B,
+);
+
+#[test]
+fn same_typed_fields() {
+    LOG.with(|it| it.borrow_mut().clear());
+    drop(Named { first: "Jane".to_owned().into(), r#last: "Doe".to_owned().into() });
+    LOG.with(|it| assert_eq!(*it.borrow(), ["first: Jane", "last: Doe"]));
+
+    let counter = &Cell::new(0);
+    let bump = || counter.set(counter.get() + 1);
+    let (a, b) = (Defer(bump.into()), Defer(bump.into()));
+    drop(Pair(a.into(), b.into()));
+    assert_eq!(counter.get(), 2);
+}
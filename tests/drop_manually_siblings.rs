@@ -0,0 +1,134 @@
+#![forbid(unsafe_code)]
+
+use ::core::cell::RefCell;
+use ::safe_manually_drop::prelude::*;
+
+#[derive(Default)]
+struct Logger {
+    lines: RefCell<Vec<String>>,
+}
+
+impl Logger {
+    fn log(&self, line: impl Into<String>) {
+        self.lines.borrow_mut().push(line.into());
+    }
+}
+
+struct RawConnection {
+    id: u32,
+}
+
+impl RawConnection {
+    fn close(self, logger: &Logger) {
+        logger.log(format!("closing connection #{}", self.id));
+    }
+}
+
+struct Config {
+    verbose: bool,
+}
+
+#[drop_manually]
+struct Connection<'logger> {
+    #[drop_manually(Self::on_drop(_, &logger, &config))]
+    raw: RawConnection,
+    logger: &'logger Logger,
+    config: Config,
+}
+
+impl<'logger> Connection<'logger> {
+    fn on_drop(raw: RawConnection, logger: &Logger, config: &Config) {
+        if config.verbose {
+            logger.log("about to close");
+        }
+        raw.close(logger);
+    }
+}
+
+#[test]
+fn hook_sees_siblings() {
+    let logger = &Logger::default();
+    let conn = Connection {
+        raw: RawConnection { id: 42 }.into(),
+        logger,
+        config: Config { verbose: true },
+    };
+    assert_eq!(conn.raw.id, 42);
+    drop(conn);
+    assert_eq!(*logger.lines.borrow(), ["about to close", "closing connection #42"]);
+}
+
+::std::thread_local! {
+    static LOG: RefCell<Vec<&'static str>> = const { RefCell::new(vec![]) };
+}
+
+struct Flush;
+
+impl Drop for Flush {
+    fn drop(&mut self) {
+        LOG.with(|it| it.borrow_mut().push("sibling dropped"));
+    }
+}
+
+#[drop_manually]
+struct Ordering(
+    Flush,
+    #[drop_manually(sibling_aware(&0, _))]
+    &'static str,
+    #[drop_manually(plain)]
+    &'static str,
+);
+
+fn sibling_aware(_: &Flush, msg: &'static str) {
+    LOG.with(|it| it.borrow_mut().push(msg));
+}
+
+fn plain(msg: &'static str) {
+    LOG.with(|it| it.borrow_mut().push(msg));
+}
+
+#[test]
+fn sibling_aware_hooks_run_before_any_field_is_dropped() {
+    drop(Ordering(
+        Flush,
+        "sibling-aware hook".into(),
+        "plain hook".into(),
+    ));
+    LOG.with(|it| {
+        assert_eq!(*it.borrow(), ["sibling-aware hook", "sibling dropped", "plain hook"]);
+    });
+}
+
+::std::thread_local! {
+    static HOOKS: RefCell<Vec<&'static str>> = const { RefCell::new(vec![]) };
+}
+
+#[drop_manually]
+struct TwoHooks {
+    #[drop_manually(panicking_hook(_, &name))]
+    a: &'static str,
+    #[drop_manually(logging_hook(_, &name))]
+    b: &'static str,
+    name: &'static str,
+}
+
+fn panicking_hook(_: &'static str, _: &&'static str) {
+    panic!("hook a");
+}
+
+fn logging_hook(field: &'static str, _: &&'static str) {
+    HOOKS.with(|it| it.borrow_mut().push(field));
+}
+
+#[test]
+fn panicking_hook_does_not_leak_later_fields() {
+    let caught = ::std::panic::catch_unwind(|| {
+        drop(TwoHooks {
+            a: "a".into(),
+            b: "b".into(),
+            name: "two hooks",
+        });
+    });
+    assert!(caught.is_err());
+    HOOKS.with(|it| assert_eq!(*it.borrow(), ["b"]));
+}
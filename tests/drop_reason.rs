@@ -0,0 +1,36 @@
+use ::core::cell::Cell;
+use ::safe_manually_drop::prelude::*;
+
+::std::thread_local! {
+    static LAST_REASON: Cell<Option<DropReason>> = const { Cell::new(None) };
+}
+
+struct Guard {
+    _field: SafeManuallyDrop<(), WithReason<Self>>,
+}
+
+impl DropManuallyWithReason<()> for Guard {
+    fn drop_manually_with_reason((): (), reason: DropReason) {
+        LAST_REASON.with(|it| it.set(Some(reason)));
+    }
+}
+
+fn new_guard() -> Guard {
+    Guard { _field: ().into() }
+}
+
+#[test]
+fn normal_drop() {
+    drop(new_guard());
+    assert_eq!(LAST_REASON.with(Cell::take), Some(DropReason::Normal));
+}
+
+#[test]
+fn unwinding_drop() {
+    let caught = ::std::panic::catch_unwind(|| {
+        let _guard = new_guard();
+        panic!("boom");
+    });
+    assert!(caught.is_err());
+    assert_eq!(LAST_REASON.with(Cell::take), Some(DropReason::Unwinding));
+}
@@ -0,0 +1,45 @@
+use ::core::cell::RefCell;
+use ::safe_manually_drop::dyn_hook::DynSafeManuallyDrop;
+
+mod common;
+use common::{log, take_log};
+
+fn shout(it: String) {
+    log(it.to_uppercase());
+}
+
+#[test]
+fn fn_pointer_hook() {
+    for (hook, expected) in [(log as fn(_), "hello"), (shout, "HELLO")] {
+        let mut it = DynSafeManuallyDrop::new(String::from("hell"), hook);
+        it.push('o');
+        assert_eq!(*it, "hello");
+        drop(it);
+        assert_eq!(take_log(), [expected]);
+    }
+}
+
+#[test]
+fn replace_hook() {
+    let mut it = DynSafeManuallyDrop::new(String::from("hello"), log as fn(_));
+    let prev = it.replace_hook(shout);
+    assert_eq!(prev as usize, log as fn(_) as usize);
+    drop(it);
+    assert_eq!(take_log(), ["HELLO"]);
+}
+
+#[test]
+fn defuse() {
+    let it = DynSafeManuallyDrop::new(String::from("hello"), log as fn(_));
+    assert_eq!(it.into_inner_defusing_impl_Drop(), "hello");
+    assert!(take_log().is_empty());
+}
+
+#[test]
+fn boxed_hook() {
+    let local = RefCell::new(vec![]);
+    let it = DynSafeManuallyDrop::new_boxed(42, |it| local.borrow_mut().push(it));
+    assert_eq!(format!("{it:?}"), "DynSafeManuallyDrop { field: 42, .. }");
+    drop(it);
+    assert_eq!(local.into_inner(), [42]);
+}
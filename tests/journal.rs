@@ -0,0 +1,100 @@
+use ::std::{fs, io, path::{Path, PathBuf}};
+use ::safe_manually_drop::journal::{Journal, Record};
+
+mod common;
+
+/// Compensation: remove a staged file.
+#[derive(Debug)]
+struct Unstage(PathBuf);
+
+impl Record for Unstage {
+    fn encode(&self) -> Vec<u8> {
+        self.0.to_str().unwrap().into()
+    }
+
+    fn decode(bytes: &[u8]) -> io::Result<Self> {
+        String::from_utf8(bytes.into())
+            .map(|s| Self(s.into()))
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    fn compensate(self) -> io::Result<()> {
+        match fs::remove_file(self.0) {
+            | Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+            | res => res,
+        }
+    }
+}
+
+fn fresh_dir(name: &str) -> PathBuf {
+    let dir = ::std::env::temp_dir().join(format!(
+        "safe-manually-drop-journal-{}-{name}",
+        common::run_id(),
+    ));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn records(journal: &Journal<Unstage>) -> usize {
+    fs::read_dir(journal.dir()).unwrap().count()
+}
+
+fn stage(
+    journal: &Journal<Unstage>,
+    path: &Path,
+) -> ::safe_manually_drop::journal::JournalGuard<Unstage> {
+    let guard = journal.guard(Unstage(path.into())).unwrap();
+    assert!(guard.record_path().exists());
+    fs::write(path, "staged").unwrap();
+    guard
+}
+
+#[test]
+fn commit_and_drop() {
+    let dir = fresh_dir("commit_and_drop");
+    let journal = Journal::<Unstage>::open(dir.join("journal")).unwrap();
+
+    let committed = dir.join("committed");
+    stage(&journal, &committed).commit().unwrap();
+    assert!(committed.exists());
+    assert_eq!(records(&journal), 0);
+
+    let dropped = dir.join("dropped");
+    drop(stage(&journal, &dropped));
+    assert!(!dropped.exists());
+    assert_eq!(records(&journal), 0);
+
+    let rolled_back = dir.join("rolled_back");
+    stage(&journal, &rolled_back).roll_back().unwrap();
+    assert!(!rolled_back.exists());
+    assert_eq!(records(&journal), 0);
+
+    assert_eq!(journal.recover().unwrap(), 0);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn recover_after_crash() {
+    let dir = fresh_dir("recover_after_crash");
+    common::assert_aborts("recover_after_crash", &[], || {
+        let journal = Journal::<Unstage>::open(dir.join("journal")).unwrap();
+        let _a = stage(&journal, &dir.join("a"));
+        let _b = stage(&journal, &dir.join("b"));
+        stage(&journal, &dir.join("c")).commit().unwrap();
+        // `kill -9`-like: no unwinding, no destructors.
+        ::std::process::abort();
+    });
+    assert!(dir.join("a").exists() && dir.join("b").exists() && dir.join("c").exists());
+
+    let journal = Journal::<Unstage>::open(dir.join("journal")).unwrap();
+    assert_eq!(records(&journal), 2);
+    // An incomplete record, as if the crash had happened in the middle of `guard()`.
+    fs::write(journal.dir().join("incomplete.tmp"), "").unwrap();
+
+    assert_eq!(journal.recover().unwrap(), 2);
+    assert!(!dir.join("a").exists() && !dir.join("b").exists());
+    assert!(dir.join("c").exists());
+    assert_eq!(records(&journal), 0);
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,28 @@
+use ::safe_manually_drop::linear::Linear;
+
+fn start(n: i32) -> Linear<Vec<i32>> {
+    Linear::new(vec![n])
+}
+
+fn push(it: Linear<Vec<i32>>, n: i32) -> Linear<Vec<i32>> {
+    it.map(|mut v| {
+        v.push(n);
+        v
+    })
+}
+
+#[test]
+fn consumed() {
+    let it = push(start(1), 2);
+    let v = it.into_inner();
+    assert_eq!(v, [1, 2]);
+}
+
+#[test]
+fn moved_around() {
+    let it = start(1);
+    let them = (it,);
+    let (it,) = them;
+    let len = it.map(|v| v.len()).into_inner();
+    assert_eq!(len, 1);
+}
@@ -0,0 +1,38 @@
+use ::safe_manually_drop::must_consume::MustConsume;
+
+mod common;
+
+#[test]
+fn consume() {
+    let mut it = MustConsume::new(vec![1]);
+    it.push(2);
+    assert_eq!(it.created_at().line(), line!() - 2);
+    assert_eq!(it.consume(), [1, 2]);
+}
+
+#[test]
+fn dropped() {
+    let line = line!() + 1;
+    let it = MustConsume::new(42_u8);
+    let caught = ::std::panic::catch_unwind(move || drop(it)).unwrap_err();
+    let msg = caught.downcast::<String>().unwrap();
+    assert_eq!(
+        *msg,
+        format!(
+            "a `MustConsume<u8>`, created at {}:{line}:14, got dropped rather than `.consume()`d",
+            file!(),
+        ),
+    );
+}
+
+#[test]
+fn dropped_during_unwinding_aborts() {
+    common::assert_aborts(
+        "dropped_during_unwinding_aborts",
+        &["some other panic", "got dropped during unwinding: aborting."],
+        || {
+            let _it = MustConsume::new(());
+            panic!("some other panic");
+        },
+    );
+}
@@ -0,0 +1,76 @@
+use ::core::cell::Cell;
+use ::safe_manually_drop::{
+    on_panic::CaughtPanic,
+    prelude::*,
+};
+
+mod common;
+
+struct Propagating(SafeManuallyDrop<(), Self>);
+
+impl DropManually<()> for Propagating {
+    fn drop_manually((): ()) {
+        panic!("propagated");
+    }
+}
+
+#[test]
+fn propagate_is_the_default() {
+    let caught = ::std::panic::catch_unwind(|| {
+        drop(Propagating(().into()));
+    });
+    let payload = caught.unwrap_err();
+    assert_eq!(payload.downcast_ref::<&str>(), Some(&"propagated"));
+}
+
+::std::thread_local! {
+    static REPORTED: Cell<Option<(String, &'static str)>> = const { Cell::new(None) };
+}
+
+struct Reporting {
+    _n: SafeManuallyDrop<u8, Self>,
+}
+
+impl DropManually<u8> for Reporting {
+    const ON_PANIC: OnPanic = OnPanic::CatchAndReport(|caught: CaughtPanic| {
+        let message = caught.message().unwrap().to_owned();
+        REPORTED.with(|it| it.set(Some((message, caught.field_type_name))));
+    });
+
+    fn drop_manually(n: u8) {
+        panic!("reported: {n}");
+    }
+}
+
+#[test]
+fn catch_and_report() {
+    drop(Reporting { _n: 42.into() });
+    assert_eq!(REPORTED.with(Cell::take), Some(("reported: 42".to_owned(), "u8")));
+
+    // Even during unwinding, there is no double panic (and thus, no abort).
+    let caught = ::std::panic::catch_unwind(|| {
+        let _reporting = Reporting { _n: 27.into() };
+        panic!("unrelated");
+    });
+    assert!(caught.is_err());
+    assert_eq!(REPORTED.with(Cell::take), Some(("reported: 27".to_owned(), "u8")));
+}
+
+struct Aborting(SafeManuallyDrop<(), Self>);
+
+impl DropManually<()> for Aborting {
+    const ON_PANIC: OnPanic = OnPanic::Abort;
+
+    fn drop_manually((): ()) {
+        panic!("about to abort");
+    }
+}
+
+#[test]
+fn abort() {
+    common::assert_aborts(
+        "abort",
+        &["about to abort", "`OnPanic::Abort` policy: aborting"],
+        || drop(Aborting(().into())),
+    );
+}
@@ -0,0 +1,69 @@
+use ::core::cell::Cell;
+use ::safe_manually_drop::poison::{PoisonCell, Poisoned};
+
+#[test]
+fn normal_drop_does_not_poison() {
+    let cell = PoisonCell::new(vec![1]);
+    cell.lock().unwrap().push(2);
+    assert!(!cell.is_poisoned());
+    assert_eq!(cell.into_inner().unwrap(), [1, 2]);
+}
+
+#[test]
+fn unwinding_poisons() {
+    let cell = PoisonCell::new(vec![1]);
+    let caught = ::std::panic::catch_unwind(::core::panic::AssertUnwindSafe(|| {
+        let mut v = cell.lock().unwrap();
+        v.push(2);
+        panic!();
+    }));
+    assert!(caught.is_err());
+    assert!(cell.is_poisoned());
+    // Inspection does not lift the poison.
+    let Err(Poisoned(v)) = cell.lock() else { unreachable!() };
+    assert_eq!(*v, [1, 2]);
+    drop(v);
+    let Err(Poisoned(mut v)) = cell.lock() else { unreachable!() };
+    v.pop();
+    v.clear_poison();
+    drop(v);
+    assert_eq!(*cell.lock().unwrap(), [1]);
+}
+
+#[test]
+#[should_panic = "already locked"]
+fn reentrant_lock() {
+    let cell = PoisonCell::new(());
+    let _guard = cell.lock();
+    let _ = cell.lock();
+}
+
+::std::thread_local! {
+    static PANICKING: Cell<bool> = const { Cell::new(false) };
+}
+
+#[test]
+fn custom_panic_check() {
+    let cell = PoisonCell::with_panic_check(0, || PANICKING.with(Cell::get));
+    *cell.lock().unwrap() += 1;
+    PANICKING.with(|it| it.set(true));
+    *cell.lock().unwrap() += 1;
+    PANICKING.with(|it| it.set(false));
+    assert!(matches!(cell.into_inner(), Err(Poisoned(2))));
+}
+
+#[test]
+fn recovery_hook() {
+    let cell = PoisonCell::new(vec![1]).with_recovery(|v| v[..1].to_vec());
+    cell.lock().unwrap().push(2);
+    assert_eq!(*cell.lock().unwrap(), [1, 2]);
+    let caught = ::std::panic::catch_unwind(::core::panic::AssertUnwindSafe(|| {
+        cell.lock().unwrap().push(3);
+        let mut v = cell.lock().unwrap();
+        v.push(4);
+        panic!();
+    }));
+    assert!(caught.is_err());
+    assert!(cell.is_poisoned());
+    assert_eq!(cell.into_inner().unwrap_err().into_inner(), [1]);
+}
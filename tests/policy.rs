@@ -0,0 +1,54 @@
+use ::core::cell::Cell;
+use ::safe_manually_drop::{policy, SafeManuallyDrop};
+
+mod common;
+
+struct CountDrops<'r>(&'r Cell<usize>);
+
+impl Drop for CountDrops<'_> {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() + 1);
+    }
+}
+
+#[test]
+fn leak() {
+    let drops = Cell::new(0);
+    drop(SafeManuallyDrop::<_, policy::Leak>::new(CountDrops(&drops)));
+    assert_eq!(drops.get(), 0);
+}
+
+#[test]
+fn drop_normally() {
+    let drops = Cell::new(0);
+    drop(SafeManuallyDrop::<_, policy::DropNormally>::new(CountDrops(&drops)));
+    assert_eq!(drops.get(), 1);
+}
+
+#[test]
+fn log() {
+    let drops = Cell::new(0);
+    drop(SafeManuallyDrop::<_, policy::Log>::new(CountDrops(&drops)));
+    assert_eq!(drops.get(), 1);
+}
+
+#[test]
+#[should_panic = "a `SafeManuallyDrop<u8, policy::PanicIfDropped>` got dropped"]
+fn panic_if_dropped() {
+    drop(SafeManuallyDrop::<u8, policy::PanicIfDropped>::new(42));
+}
+
+#[test]
+fn panic_if_dropped_defused() {
+    let it = SafeManuallyDrop::<u8, policy::PanicIfDropped>::new(42);
+    assert_eq!(it.into_inner_defusing_impl_Drop(), 42);
+}
+
+#[test]
+fn abort() {
+    common::assert_aborts(
+        "abort",
+        &["a `SafeManuallyDrop<u8, policy::Abort>` got dropped"],
+        || drop(SafeManuallyDrop::<u8, policy::Abort>::new(42)),
+    );
+}
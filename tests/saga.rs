@@ -0,0 +1,82 @@
+use ::core::cell::RefCell;
+use ::safe_manually_drop::{prelude::*, saga::Saga};
+
+#[test]
+fn compensations_run_in_reverse_order() {
+    let log = RefCell::new(vec![]);
+    let saga =
+        Saga::new()
+            .push("dir", |it| log.borrow_mut().push(format!("rm {it}")))
+            .push(String::from("file"), |it| log.borrow_mut().push(format!("rm {it}")))
+            .push_fn(|| log.borrow_mut().push("unregister".into()))
+    ;
+    assert!(log.borrow().is_empty());
+    drop(saga);
+    assert_eq!(log.into_inner(), ["unregister", "rm file", "rm dir"]);
+}
+
+#[test]
+fn complete() {
+    let log = RefCell::new(vec![]);
+    let saga = Saga::new().push_fn(|| log.borrow_mut().push("undo"));
+    saga.push_fn(|| log.borrow_mut().push("undo")).complete();
+    assert!(log.into_inner().is_empty());
+}
+
+fn setup(log: &RefCell<Vec<&'static str>>, fail_at: usize) -> Result<(), usize> {
+    let step = |i| if i == fail_at { Err(i) } else { Ok(()) };
+    step(0)?;
+    let saga = Saga::new().push_fn(|| log.borrow_mut().push("undo 0"));
+    step(1)?;
+    let saga = saga.push_fn(|| log.borrow_mut().push("undo 1"));
+    step(2)?;
+    saga.complete();
+    Ok(())
+}
+
+#[test]
+fn early_return() {
+    for (fail_at, expected) in [(0, &[][..]), (1, &["undo 0"]), (2, &["undo 1", "undo 0"])] {
+        let log = RefCell::new(vec![]);
+        assert_eq!(setup(&log, fail_at), Err(fail_at));
+        assert_eq!(log.into_inner(), expected);
+    }
+    let log = RefCell::new(vec![]);
+    assert_eq!(setup(&log, 3), Ok(()));
+    assert!(log.into_inner().is_empty());
+}
+
+#[test]
+fn panicking_compensation_does_not_skip_the_others() {
+    let log = RefCell::new(vec![]);
+    let caught = ::std::panic::catch_unwind(::core::panic::AssertUnwindSafe(|| {
+        Saga::new()
+            .push_fn(|| log.borrow_mut().push("undo 0"))
+            .push_fn(|| panic!("undo 1"))
+            .push_fn(|| log.borrow_mut().push("undo 2"))
+            .abort()
+    }));
+    assert!(caught.is_err());
+    assert_eq!(log.into_inner(), ["undo 2", "undo 0"]);
+}
+
+::std::thread_local! {
+    static UNREGISTERED: RefCell<Vec<u32>> = const { RefCell::new(vec![]) };
+}
+
+enum Unregister {}
+
+impl DropManually<u32> for Unregister {
+    fn drop_manually(id: u32) {
+        UNREGISTERED.with(|it| it.borrow_mut().push(id));
+    }
+}
+
+#[test]
+fn drop_manually_impl_as_undo() {
+    Saga::new()
+        .push(1, Unregister::drop_manually)
+        .push(2, Unregister::drop_manually)
+        .abort();
+    assert_eq!(UNREGISTERED.with(RefCell::take), [2, 1]);
+}
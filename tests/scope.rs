@@ -0,0 +1,79 @@
+use ::core::cell::RefCell;
+use ::safe_manually_drop::scope;
+
+#[test]
+fn dropped_guard_runs_once() {
+    let log = RefCell::new(vec![]);
+    scope(|s| {
+        let guard = s.guard(0, |it| log.borrow_mut().push(it));
+        drop(guard);
+        assert_eq!(*log.borrow(), [0]);
+    });
+    assert_eq!(log.into_inner(), [0]);
+}
+
+#[test]
+fn forgotten_guard_runs_at_scope_end() {
+    let log = RefCell::new(vec![]);
+    scope(|s| {
+        ::core::mem::forget(s.guard(0, |it| log.borrow_mut().push(it)));
+        assert!(log.borrow().is_empty());
+    });
+    assert_eq!(log.into_inner(), [0]);
+}
+
+#[test]
+fn leftovers_run_in_reverse_order() {
+    let log = RefCell::new(vec![]);
+    let ret = scope(|s| {
+        for i in 0..5 {
+            let mut guard = s.guard(i, |it| log.borrow_mut().push(it));
+            *guard.borrow_mut() *= 10;
+            if i % 2 == 0 {
+                ::core::mem::forget(guard);
+            }
+        }
+        log.borrow().clone()
+    });
+    assert_eq!(ret, [10, 30]);
+    assert_eq!(log.into_inner(), [10, 30, 40, 20, 0]);
+}
+
+#[test]
+fn defuse() {
+    let log = RefCell::new(vec![]);
+    scope(|s| {
+        let guard = s.guard(String::from("value"), |it| log.borrow_mut().push(it));
+        assert_eq!(*guard.borrow(), "value");
+        assert_eq!(guard.defuse(), "value");
+    });
+    assert!(log.into_inner().is_empty());
+}
+
+#[test]
+fn leftovers_run_on_unwind() {
+    let log = RefCell::new(vec![]);
+    let caught = ::std::panic::catch_unwind(::core::panic::AssertUnwindSafe(|| {
+        scope(|s| {
+            ::core::mem::forget(s.guard(0, |it| log.borrow_mut().push(it)));
+            ::core::mem::forget(s.guard(1, |it| log.borrow_mut().push(it)));
+            panic!();
+        })
+    }));
+    assert!(caught.is_err());
+    assert_eq!(log.into_inner(), [1, 0]);
+}
+
+#[test]
+fn panicking_leftover_does_not_skip_the_others() {
+    let log = RefCell::new(vec![]);
+    let caught = ::std::panic::catch_unwind(::core::panic::AssertUnwindSafe(|| {
+        scope(|s| {
+            ::core::mem::forget(s.guard(0, |it| log.borrow_mut().push(it)));
+            ::core::mem::forget(s.guard(1, |_| panic!()));
+            ::core::mem::forget(s.guard(2, |it| log.borrow_mut().push(it)));
+        })
+    }));
+    assert!(caught.is_err());
+    assert_eq!(log.into_inner(), [2, 0]);
+}
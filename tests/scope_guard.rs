@@ -0,0 +1,56 @@
+use ::safe_manually_drop::scope_guard::{ScopeGuard, ScopeGuardFields};
+
+fn with_and_without_panic(
+    body: impl Fn(&mut Vec<&'static str>, bool),
+) -> [Vec<&'static str>; 2]
+{
+    [false, true].map(|panics| {
+        let mut log = vec![];
+        let _ = ::std::panic::catch_unwind(::core::panic::AssertUnwindSafe(|| {
+            body(&mut log, panics);
+        }));
+        log
+    })
+}
+
+#[test]
+fn always() {
+    let logs = with_and_without_panic(|log, panics| {
+        let mut log = ScopeGuard::new(log, |log| log.push("cleanup"));
+        log.push("work");
+        assert!(!panics);
+    });
+    assert_eq!(logs, [["work", "cleanup"]; 2]);
+}
+
+#[test]
+fn on_success() {
+    let logs = with_and_without_panic(|log, panics| {
+        let mut log = ScopeGuard::on_success(log, |log| log.push("commit"));
+        log.push("work");
+        assert!(!panics);
+    });
+    assert_eq!(logs, [&["work", "commit"][..], &["work"]]);
+}
+
+#[test]
+fn on_unwind() {
+    let logs = with_and_without_panic(|log, panics| {
+        let mut log = ScopeGuard::on_unwind(log, |log| log.push("roll back"));
+        log.push("work");
+        assert!(!panics);
+    });
+    assert_eq!(logs, [&["work"][..], &["work", "roll back"]]);
+}
+
+#[test]
+fn defuse() {
+    let mut log = vec![];
+    let guard = ScopeGuardFields {
+        state: &mut log,
+        on_drop: |log: &mut Vec<_>| log.push("cleanup"),
+    }.arm();
+    let ScopeGuardFields { state, on_drop: _ } = guard.defuse();
+    state.push("defused");
+    assert_eq!(log, ["defused"]);
+}
@@ -0,0 +1,51 @@
+use ::core::cell::Cell;
+use ::safe_manually_drop::prelude::*;
+use ::serde_derive::{Deserialize, Serialize};
+
+::std::thread_local! {
+    static ROLLED_BACK: Cell<usize> = const { Cell::new(0) };
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct RawTransaction {
+    id: u32,
+}
+
+impl RawTransaction {
+    fn commit(self) {}
+
+    fn roll_back(self) {
+        ROLLED_BACK.with(|it| it.set(it.get() + 1));
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Transaction {
+    raw_txn: SafeManuallyDrop<RawTransaction, Self>,
+    label: String,
+}
+
+impl DropManually<RawTransaction> for Transaction {
+    fn drop_manually(raw_txn: RawTransaction) {
+        raw_txn.roll_back();
+    }
+}
+
+#[test]
+fn transparent_serde_roundtrip() {
+    let txn = Transaction {
+        raw_txn: RawTransaction { id: 42 }.into(),
+        label: "hello".into(),
+    };
+    let json = ::serde_json::to_string(&txn).unwrap();
+    assert_eq!(json, r#"{"raw_txn":{"id":42},"label":"hello"}"#);
+
+    let rolled_back = ROLLED_BACK.with(Cell::get);
+    let deserialized: Transaction = ::serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized, txn);
+    txn.raw_txn.into_inner_defusing_impl_Drop().commit();
+    assert_eq!(ROLLED_BACK.with(Cell::get), rolled_back);
+    // The deserialized instance has its `DropManually` logic armed.
+    drop(deserialized);
+    assert_eq!(ROLLED_BACK.with(Cell::get), rolled_back + 1);
+}
@@ -0,0 +1,82 @@
+use ::safe_manually_drop::{prelude::*, try_drop::with_scoped_sink};
+
+mod common;
+use common::{log, take_log};
+
+#[derive(Debug, Clone, PartialEq)]
+struct Files<T : ::core::fmt::Debug> {
+    input: SafeManuallyDrop<T, Self, Input>,
+    output: SafeManuallyDrop<T, Self, Output>,
+    plain: SafeManuallyDrop<T, Self>,
+}
+
+enum Input {}
+impl<T : ::core::fmt::Debug> DropManually<T, Input> for Files<T> {
+    fn drop_manually(it: T) {
+        log(format!("input: {it:?}"));
+    }
+}
+
+enum Output {}
+impl<T : ::core::fmt::Debug> DropManually<T, Output> for Files<T> {
+    fn drop_manually(it: T) {
+        log(format!("output: {it:?}"));
+    }
+}
+
+impl<T : ::core::fmt::Debug> DropManually<T> for Files<T> {
+    fn drop_manually(it: T) {
+        log(format!("plain: {it:?}"));
+    }
+}
+
+#[test]
+fn same_field_type_distinct_hooks() {
+    let files = Files {
+        input: SafeManuallyDrop::new("a"),
+        output: "b".into(),
+        plain: "c".into(),
+    };
+    assert_eq!(files.clone(), files);
+    take_log();
+    drop(files);
+    assert_eq!(take_log(), [
+        r#"input: "a""#,
+        r#"output: "b""#,
+        r#"plain: "c""#,
+    ]);
+}
+
+struct Flushes {
+    data: SafeManuallyDrop<u8, Fallible<Self>, Data>,
+    meta: SafeManuallyDrop<u8, Fallible<Self>, Meta>,
+}
+
+enum Data {}
+impl TryDropManually<u8, Data> for Flushes {
+    type Error = &'static str;
+
+    fn try_drop_manually(_: u8) -> Result<(), &'static str> {
+        Err("data")
+    }
+}
+
+enum Meta {}
+impl TryDropManually<u8, Meta> for Flushes {
+    type Error = &'static str;
+
+    fn try_drop_manually(_: u8) -> Result<(), &'static str> {
+        Err("meta")
+    }
+}
+
+#[test]
+fn tagged_adapters() {
+    let mut errors = vec![];
+    with_scoped_sink(&mut |err| errors.push(*err.downcast_ref::<&str>().unwrap()), || {
+        let flushes = Flushes { data: 0.into(), meta: 0.into() };
+        assert_eq!(flushes.meta.close(), Err("meta"));
+        drop(flushes.data);
+    });
+    assert_eq!(errors, ["data"]);
+}
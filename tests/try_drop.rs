@@ -0,0 +1,83 @@
+use ::core::cell::Cell;
+use ::safe_manually_drop::{
+    prelude::*,
+    try_drop::{set_global_sink, with_scoped_sink, DropError},
+};
+
+#[derive(Debug, PartialEq)]
+struct CloseError(u8);
+
+struct File {
+    _fd: SafeManuallyDrop<u8, Fallible<Self>>,
+}
+
+impl TryDropManually<u8> for File {
+    type Error = CloseError;
+
+    fn try_drop_manually(fd: u8) -> Result<(), CloseError> {
+        if fd % 2 == 0 {
+            Ok(())
+        } else {
+            Err(CloseError(fd))
+        }
+    }
+}
+
+fn file(fd: u8) -> File {
+    File { _fd: fd.into() }
+}
+
+#[test]
+fn scoped_sinks() {
+    let mut outer = vec![];
+    let mut inner = vec![];
+    with_scoped_sink(&mut |err| outer.push(err.downcast_ref::<CloseError>().unwrap().0), || {
+        drop(file(1));
+        drop(file(2));
+        with_scoped_sink(&mut |err| inner.push(err.to_string()), || {
+            drop(file(3));
+        });
+        drop(file(5));
+    });
+    assert_eq!(outer, [1, 5]);
+    assert_eq!(inner, [
+        "`<try_drop::File as TryDropManually<u8>>::try_drop_manually()` failed: CloseError(3)",
+    ]);
+}
+
+#[test]
+fn scoped_sink_is_restored_on_unwind() {
+    let mut errors = vec![];
+    with_scoped_sink(&mut |err| errors.push(err.downcast_ref::<CloseError>().unwrap().0), || {
+        let caught = ::std::panic::catch_unwind(|| {
+            with_scoped_sink(&mut |_| unreachable!(), || panic!());
+        });
+        assert!(caught.is_err());
+        drop(file(7));
+    });
+    assert_eq!(errors, [7]);
+}
+
+::std::thread_local! {
+    static GLOBALLY_REPORTED: Cell<Option<u8>> = const { Cell::new(None) };
+}
+
+#[test]
+fn global_sink() {
+    fn sink(err: &DropError<'_>) {
+        if let Some(&CloseError(fd)) = err.downcast_ref() {
+            GLOBALLY_REPORTED.with(|it| it.set(Some(fd)));
+        }
+    }
+    set_global_sink(sink);
+    drop(file(9));
+    assert_eq!(GLOBALLY_REPORTED.with(Cell::take), Some(9));
+
+    // Scoped sinks take precedence.
+    with_scoped_sink(&mut |_| {}, || drop(file(11)));
+    assert_eq!(GLOBALLY_REPORTED.with(Cell::take), None);
+
+    // Errors from within a scoped sink skip the scoped sinks.
+    with_scoped_sink(&mut |_| drop(file(13)), || drop(file(15)));
+    assert_eq!(GLOBALLY_REPORTED.with(Cell::take), Some(13));
+}
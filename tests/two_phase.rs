@@ -0,0 +1,110 @@
+use ::core::cell::RefCell;
+use ::safe_manually_drop::{
+    transaction::{Transactional, TxnGuard},
+    two_phase::{Coordinator, Prepare},
+};
+
+type Log<'r> = &'r RefCell<Vec<String>>;
+
+struct Participant<'r> {
+    name: &'static str,
+    fail_prepare: bool,
+    log: Log<'r>,
+}
+
+impl Transactional for Participant<'_> {
+    fn commit(self) {
+        self.log.borrow_mut().push(format!("commit {}", self.name));
+    }
+
+    fn roll_back(self) {
+        self.log.borrow_mut().push(format!("roll back {}", self.name));
+    }
+}
+
+impl Prepare for Participant<'_> {
+    type Error = &'static str;
+
+    fn prepare(&mut self) -> Result<(), &'static str> {
+        self.log.borrow_mut().push(format!("prepare {}", self.name));
+        if self.fail_prepare { Err(self.name) } else { Ok(()) }
+    }
+}
+
+/// Another participant type, with another error type.
+struct Metadata<'r>(Log<'r>);
+
+impl Transactional for Metadata<'_> {
+    fn commit(self) {
+        self.0.borrow_mut().push("commit metadata".into());
+    }
+
+    fn roll_back(self) {
+        self.0.borrow_mut().push("roll back metadata".into());
+    }
+}
+
+impl Prepare for Metadata<'_> {
+    type Error = ::core::convert::Infallible;
+
+    fn prepare(&mut self) -> Result<(), ::core::convert::Infallible> {
+        self.0.borrow_mut().push("prepare metadata".into());
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Error(&'static str);
+
+impl From<&'static str> for Error {
+    fn from(name: &'static str) -> Self {
+        Self(name)
+    }
+}
+
+impl From<::core::convert::Infallible> for Error {
+    fn from(unreachable: ::core::convert::Infallible) -> Self {
+        match unreachable {}
+    }
+}
+
+fn coordinator(
+    log: Log<'_>,
+    fail_prepare: bool,
+) -> Coordinator<(TxnGuard<Participant<'_>>, TxnGuard<Participant<'_>>, TxnGuard<Metadata<'_>>)>
+{
+    Coordinator::new((
+        TxnGuard::new(Participant { name: "a", fail_prepare: false, log }),
+        TxnGuard::new(Participant { name: "b", fail_prepare, log }),
+        TxnGuard::new(Metadata(log)),
+    ))
+}
+
+#[test]
+fn all_prepared_then_all_committed() {
+    let log = RefCell::new(vec![]);
+    assert_eq!(coordinator(&log, false).commit::<Error>(), Ok(()));
+    assert_eq!(log.into_inner(), [
+        "prepare a", "prepare b", "prepare metadata",
+        "commit a", "commit b", "commit metadata",
+    ]);
+}
+
+#[test]
+fn failed_prepare_rolls_everything_back() {
+    let log = RefCell::new(vec![]);
+    assert_eq!(coordinator(&log, true).commit::<Error>(), Err(Error("b")));
+    assert_eq!(log.into_inner(), [
+        "prepare a", "prepare b",
+        "roll back a", "roll back b", "roll back metadata",
+    ]);
+}
+
+#[test]
+fn early_drop_rolls_everything_back() {
+    let log = RefCell::new(vec![]);
+    let mut coordinator = coordinator(&log, false);
+    coordinator.participants_mut().0.name = "a'";
+    drop(coordinator);
+    assert_eq!(log.into_inner(), ["roll back a'", "roll back b", "roll back metadata"]);
+}
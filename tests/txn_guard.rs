@@ -0,0 +1,155 @@
+use ::std::{cell::RefCell, rc::{Rc, Weak}};
+use ::safe_manually_drop::transaction::{Savepoints, SavepointToken, Transactional, TxnGuard};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DbState {
+    Committed,
+    RolledBack,
+}
+
+struct RawTransaction<'r> {
+    db_state: &'r RefCell<Option<DbState>>,
+    writes: Vec<u8>,
+}
+
+impl Transactional for RawTransaction<'_> {
+    fn commit(self) {
+        *self.db_state.borrow_mut() = Some(DbState::Committed);
+    }
+
+    fn roll_back(self) {
+        *self.db_state.borrow_mut() = Some(DbState::RolledBack);
+    }
+}
+
+struct Transaction<'r> {
+    raw_txn: TxnGuard<RawTransaction<'r>>,
+    _some_other_owned_resource: Weak<()>,
+}
+
+impl<'r> Transaction<'r> {
+    fn new(db_state: &'r RefCell<Option<DbState>>, resource: &Rc<()>) -> Self {
+        Self {
+            raw_txn: TxnGuard::new(RawTransaction { db_state, writes: vec![] }),
+            _some_other_owned_resource: Rc::downgrade(resource),
+        }
+    }
+
+    fn commit(self) {
+        self.raw_txn.commit();
+    }
+
+    fn roll_back(self) {
+        self.raw_txn.roll_back();
+    }
+}
+
+#[test]
+fn commit_and_roll_back() {
+    let resource = Rc::new(());
+    for (end, expected) in [
+        ("commit", DbState::Committed),
+        ("drop", DbState::RolledBack),
+        ("roll_back", DbState::RolledBack),
+    ]
+    {
+        let db_state = RefCell::new(None);
+        let mut txn = Transaction::new(&db_state, &resource);
+        txn.raw_txn.writes.push(42);
+        assert_eq!(Rc::weak_count(&resource), 1);
+        match end {
+            | "commit" => txn.commit(),
+            | "drop" => drop(txn),
+            | _ => txn.roll_back(),
+        }
+        assert_eq!(*db_state.borrow(), Some(expected));
+        // The other fields were dropped normally.
+        assert_eq!(Rc::weak_count(&resource), 0);
+    }
+}
+
+#[test]
+fn rolls_back_on_unwind() {
+    let db_state = RefCell::new(None);
+    let _ = ::std::panic::catch_unwind(::core::panic::AssertUnwindSafe(|| {
+        let _txn = TxnGuard::new(RawTransaction { db_state: &db_state, writes: vec![] });
+        panic!();
+    }));
+    assert_eq!(*db_state.borrow(), Some(DbState::RolledBack));
+}
+
+#[test]
+fn into_inner() {
+    let db_state = RefCell::new(None);
+    let raw_txn = TxnGuard::new(RawTransaction { db_state: &db_state, writes: vec![1] });
+    let raw_txn = raw_txn.into_inner();
+    assert_eq!(raw_txn.writes, [1]);
+    drop(raw_txn);
+    assert_eq!(*db_state.borrow(), None);
+}
+
+struct Writes<'r> {
+    db: &'r RefCell<Vec<&'static str>>,
+    pending: Vec<&'static str>,
+}
+
+impl Transactional for Writes<'_> {
+    fn commit(self) {
+        self.db.borrow_mut().extend(self.pending);
+    }
+
+    fn roll_back(self) {}
+}
+
+impl Savepoints for Writes<'_> {
+    type Savepoint = usize;
+
+    fn savepoint(&mut self, _: SavepointToken) -> usize {
+        self.pending.len()
+    }
+
+    fn roll_back_to(&mut self, len: usize, _: SavepointToken) {
+        self.pending.truncate(len);
+    }
+
+    fn release(&mut self, _: usize, _: SavepointToken) {}
+}
+
+#[test]
+fn savepoints() {
+    let db = RefCell::new(vec![]);
+    let mut txn = TxnGuard::new(Writes { db: &db, pending: vec![] });
+    txn.pending.push("a");
+    {
+        let mut child = txn.savepoint();
+        child.pending.push("b");
+        {
+            let mut grandchild = child.savepoint();
+            grandchild.pending.push("c");
+            grandchild.commit();
+        }
+        {
+            let mut grandchild = child.savepoint();
+            grandchild.pending.push("d");
+        }
+        assert_eq!(child.pending, ["a", "b", "c"]);
+        child.commit();
+    }
+    {
+        let mut child = txn.savepoint();
+        child.pending.push("e");
+        child.roll_back();
+    }
+    assert_eq!(txn.pending, ["a", "b", "c"]);
+    txn.commit();
+    assert_eq!(*db.borrow(), ["a", "b", "c"]);
+
+    // Dropping the parent rolls back everything, including committed children.
+    let mut txn = TxnGuard::new(Writes { db: &db, pending: vec![] });
+    txn.savepoint().pending.push("f");
+    let mut child = txn.savepoint();
+    child.pending.push("g");
+    child.commit();
+    drop(txn);
+    assert_eq!(*db.borrow(), ["a", "b", "c"]);
+}